@@ -0,0 +1,198 @@
+use crate::error::CommandError;
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstalledBrowser {
+    pub name: String,
+    pub path: String,
+    pub version: Option<String>,
+}
+
+#[tauri::command]
+pub async fn chrome_list_browsers() -> Result<Vec<InstalledBrowser>, CommandError> {
+    // Each browser takes a moment to print its version, so ask them all at once
+    let browsers = browser_candidates()
+        .into_iter()
+        .map(|(name, path)| async move {
+            InstalledBrowser {
+                name: name.to_string(),
+                version: browser_version(&path).await,
+                path: path.to_string_lossy().to_string(),
+            }
+        });
+    Ok(join_all(browsers).await)
+}
+
+// Chromium-family browsers installed on this machine, in launch preference order
+pub fn browser_candidates() -> Vec<(&'static str, PathBuf)> {
+    let mut found: Vec<(&'static str, PathBuf)> = Vec::new();
+
+    for (name, path) in known_locations() {
+        // Several launchers can lead to the same binary, e.g. google-chrome and google-chrome-stable
+        let resolved = launched_binary(&path);
+        if found
+            .iter()
+            .any(|(_, seen)| launched_binary(seen) == resolved)
+        {
+            continue;
+        }
+        found.push((name, path));
+    }
+
+    found
+}
+
+// The binary a launcher ends up running. Snap launchers all link to the snap
+// tool itself, which picks the app by the name it was called with
+fn launched_binary(path: &Path) -> PathBuf {
+    match std::fs::canonicalize(path) {
+        Ok(resolved) if resolved.file_name().is_some_and(|name| name == "snap") => {
+            path.to_path_buf()
+        }
+        Ok(resolved) => resolved,
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn known_locations() -> Vec<(&'static str, PathBuf)> {
+    let roots: Vec<PathBuf> = ["PROGRAMFILES", "PROGRAMFILES(X86)", "LOCALAPPDATA"]
+        .iter()
+        .filter_map(|var| std::env::var_os(var).map(PathBuf::from))
+        .collect();
+
+    let mut locations = Vec::new();
+    for (name, relative) in [
+        ("Google Chrome", r"Google\Chrome\Application\chrome.exe"),
+        ("Google Chrome Beta", r"Google\Chrome Beta\Application\chrome.exe"),
+        ("Google Chrome Canary", r"Google\Chrome SxS\Application\chrome.exe"),
+        ("Chromium", r"Chromium\Application\chrome.exe"),
+        ("Microsoft Edge", r"Microsoft\Edge\Application\msedge.exe"),
+        ("Brave", r"BraveSoftware\Brave-Browser\Application\brave.exe"),
+        ("Vivaldi", r"Vivaldi\Application\vivaldi.exe"),
+    ] {
+        for root in &roots {
+            let path = root.join(relative);
+            if path.is_file() {
+                locations.push((name, path));
+            }
+        }
+    }
+    locations
+}
+
+#[cfg(target_os = "macos")]
+fn known_locations() -> Vec<(&'static str, PathBuf)> {
+    let mut roots = vec![PathBuf::from("/Applications")];
+    if let Some(home) = dirs::home_dir() {
+        roots.push(home.join("Applications"));
+    }
+
+    let mut locations = Vec::new();
+    for (name, relative) in [
+        ("Google Chrome", "Google Chrome.app/Contents/MacOS/Google Chrome"),
+        ("Google Chrome Beta", "Google Chrome Beta.app/Contents/MacOS/Google Chrome Beta"),
+        ("Google Chrome Canary", "Google Chrome Canary.app/Contents/MacOS/Google Chrome Canary"),
+        ("Chromium", "Chromium.app/Contents/MacOS/Chromium"),
+        ("Microsoft Edge", "Microsoft Edge.app/Contents/MacOS/Microsoft Edge"),
+        ("Brave", "Brave Browser.app/Contents/MacOS/Brave Browser"),
+        ("Vivaldi", "Vivaldi.app/Contents/MacOS/Vivaldi"),
+    ] {
+        for root in &roots {
+            let path = root.join(relative);
+            if path.is_file() {
+                locations.push((name, path));
+            }
+        }
+    }
+    locations
+}
+
+#[cfg(target_os = "linux")]
+fn known_locations() -> Vec<(&'static str, PathBuf)> {
+    let mut locations = Vec::new();
+
+    for (name, command) in [
+        ("Google Chrome", "google-chrome"),
+        ("Google Chrome", "google-chrome-stable"),
+        ("Google Chrome Beta", "google-chrome-beta"),
+        ("Google Chrome Unstable", "google-chrome-unstable"),
+        ("Chromium", "chromium"),
+        ("Chromium", "chromium-browser"),
+        ("Microsoft Edge", "microsoft-edge"),
+        ("Microsoft Edge", "microsoft-edge-stable"),
+        ("Brave", "brave-browser"),
+        ("Brave", "brave"),
+        ("Vivaldi", "vivaldi"),
+        ("Vivaldi", "vivaldi-stable"),
+    ] {
+        if let Some(path) = find_in_path(command) {
+            locations.push((name, path));
+        }
+    }
+
+    // Snap and flatpak installs are only on PATH when their bin dirs were added to it
+    let mut exports = vec![
+        PathBuf::from("/snap/bin"),
+        PathBuf::from("/var/lib/flatpak/exports/bin"),
+    ];
+    if let Some(home) = dirs::home_dir() {
+        exports.push(home.join(".local/share/flatpak/exports/bin"));
+    }
+
+    for (name, file) in [
+        ("Chromium (snap)", "chromium"),
+        ("Brave (snap)", "brave"),
+        ("Google Chrome (flatpak)", "com.google.Chrome"),
+        ("Chromium (flatpak)", "org.chromium.Chromium"),
+        ("Microsoft Edge (flatpak)", "com.microsoft.Edge"),
+        ("Brave (flatpak)", "com.brave.Browser"),
+    ] {
+        for dir in &exports {
+            let path = dir.join(file);
+            if path.is_file() {
+                locations.push((name, path));
+            }
+        }
+    }
+
+    locations
+}
+
+#[cfg(target_os = "linux")]
+fn find_in_path(command: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(command))
+        .find(|path| path.is_file())
+}
+
+// Version as the browser reports it, e.g. "Google Chrome 126.0.6478.126"
+#[cfg(not(target_os = "windows"))]
+async fn browser_version(path: &Path) -> Option<String> {
+    let output = tokio::process::Command::new(path)
+        .arg("--version")
+        .output()
+        .await
+        .ok()?;
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!version.is_empty()).then_some(version)
+}
+
+// Windows builds don't print a version, but install it in a directory named after it
+#[cfg(target_os = "windows")]
+async fn browser_version(path: &Path) -> Option<String> {
+    std::fs::read_dir(path.parent()?)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.split('.').all(|part| part.parse::<u32>().is_ok()))
+        .max_by_key(|name| {
+            name.split('.')
+                .map(|part| part.parse::<u32>().unwrap_or(0))
+                .collect::<Vec<_>>()
+        })
+}
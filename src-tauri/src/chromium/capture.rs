@@ -0,0 +1,329 @@
+use crate::chromium::cdp::CallOptions;
+use crate::chromium::lib::cdp_client;
+use crate::chromium::protocol::page;
+use crate::chromium::sessions::SessionRegistry;
+use crate::chromium::tabs::resolve_target;
+use crate::chromium::vimium::PageElement;
+use crate::error::CommandError;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScreenshotOptions {
+    pub mode: Option<String>, // "viewport" (default), "full_page", "element"
+    pub element: Option<PageElement>, // Element to clip to in "element" mode
+    pub format: Option<String>, // "png" (default) or "jpeg"
+    pub quality: Option<u8>, // JPEG quality, 0-100
+    pub path: Option<String>, // Write the image here instead of returning it
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PdfOptions {
+    pub paper_format: Option<String>, // "letter" (default), "legal", "tabloid", "a3", "a4", "a5"
+    pub paper_width: Option<f64>, // Inches, overrides paper_format
+    pub paper_height: Option<f64>, // Inches, overrides paper_format
+    pub margin_top: Option<f64>, // Inches, 0.4 by default
+    pub margin_bottom: Option<f64>,
+    pub margin_left: Option<f64>,
+    pub margin_right: Option<f64>,
+    pub landscape: Option<bool>,
+    pub print_background: Option<bool>,
+    pub path: Option<String>, // Write the PDF here instead of returning it
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CaptureResult {
+    pub format: String,
+    pub path: Option<String>,
+    pub data: Option<String>, // Base64-encoded (MHTML as plain text), when no path was given
+}
+
+const DEFAULT_PDF_MARGIN: f64 = 0.4;
+
+impl PdfOptions {
+    // Page.printToPDF params, with defaults filled in for what was left out
+    fn print_command(&self) -> Result<page::PrintToPdf, String> {
+        let (format_width, format_height) =
+            paper_size(self.paper_format.as_deref().unwrap_or("letter"))?;
+
+        Ok(page::PrintToPdf {
+            landscape: self.landscape.unwrap_or(false),
+            print_background: self.print_background.unwrap_or(false),
+            paper_width: self.paper_width.unwrap_or(format_width),
+            paper_height: self.paper_height.unwrap_or(format_height),
+            margin_top: self.margin_top.unwrap_or(DEFAULT_PDF_MARGIN),
+            margin_bottom: self.margin_bottom.unwrap_or(DEFAULT_PDF_MARGIN),
+            margin_left: self.margin_left.unwrap_or(DEFAULT_PDF_MARGIN),
+            margin_right: self.margin_right.unwrap_or(DEFAULT_PDF_MARGIN),
+        })
+    }
+}
+
+#[tauri::command]
+pub async fn chrome_capture_screenshot(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    options: ScreenshotOptions,
+    target_id: Option<String>,
+    call_options: Option<CallOptions>,
+) -> Result<CaptureResult, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let format = options.format.unwrap_or_else(|| "png".to_string());
+    if format != "png" && format != "jpeg" {
+        return Err(format!("Unsupported screenshot format: {}", format).into());
+    }
+
+    let target = resolve_target(&session, target_id.as_deref()).await?;
+    let client = cdp_client(&session, &target)
+        .await?
+        .with_options(call_options);
+
+    let mut command = page::CaptureScreenshot {
+        format: Some(format.clone()),
+        quality: options.quality.filter(|_| format == "jpeg"),
+        ..Default::default()
+    };
+
+    match options.mode.as_deref().unwrap_or("viewport") {
+        "viewport" => {}
+        "full_page" => {
+            let metrics = client.execute(page::GetLayoutMetrics {}).await?;
+            command.clip = Some(page::Viewport {
+                x: 0.0,
+                y: 0.0,
+                width: metrics.css_content_size.width,
+                height: metrics.css_content_size.height,
+                scale: 1.0,
+            });
+            command.capture_beyond_viewport = Some(true);
+        }
+        "element" => {
+            let element = options
+                .element
+                .ok_or("Element screenshots need the element to capture")?;
+            // PageElement geometry is already in page coordinates
+            command.clip = Some(page::Viewport {
+                x: element.x,
+                y: element.y,
+                width: element.width,
+                height: element.height,
+                scale: 1.0,
+            });
+            command.capture_beyond_viewport = Some(true);
+        }
+        mode => return Err(format!("Unknown screenshot mode: {}", mode).into()),
+    }
+
+    let screenshot = client
+        .execute(command)
+        .await
+        .map_err(|e| CommandError::from(e).context("Screenshot failed"))?;
+
+    save_or_return(format, screenshot.data, options.path)
+}
+
+#[tauri::command]
+pub async fn chrome_save_pdf(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    options: PdfOptions,
+    target_id: Option<String>,
+    call_options: Option<CallOptions>,
+) -> Result<CaptureResult, CommandError> {
+    let session = sessions.get(&session_id)?;
+    let print = options.print_command()?;
+
+    let target = resolve_target(&session, target_id.as_deref()).await?;
+    let client = cdp_client(&session, &target)
+        .await?
+        .with_options(call_options);
+
+    let pdf = client
+        .execute(print)
+        .await
+        .map_err(|e| CommandError::from(e).context("PDF export failed"))?;
+
+    save_or_return("pdf".to_string(), pdf.data, options.path)
+}
+
+#[tauri::command]
+pub async fn chrome_save_mhtml(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    path: Option<String>,
+    target_id: Option<String>,
+    call_options: Option<CallOptions>,
+) -> Result<CaptureResult, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let target = resolve_target(&session, target_id.as_deref()).await?;
+    let client = cdp_client(&session, &target)
+        .await?
+        .with_options(call_options);
+
+    let snapshot = client
+        .execute(page::CaptureSnapshot {
+            format: "mhtml".to_string(),
+        })
+        .await
+        .map_err(|e| CommandError::from(e).context("MHTML export failed"))?;
+
+    // Unlike the other captures, MHTML comes back as text rather than base64
+    match path {
+        Some(path) => {
+            std::fs::write(&path, snapshot.data)
+                .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+            println!("Saved mhtml capture to {}", path);
+            Ok(CaptureResult {
+                format: "mhtml".to_string(),
+                path: Some(path),
+                data: None,
+            })
+        }
+        None => Ok(CaptureResult {
+            format: "mhtml".to_string(),
+            path: None,
+            data: Some(snapshot.data),
+        }),
+    }
+}
+
+// Width and height in inches of the named paper format
+fn paper_size(format: &str) -> Result<(f64, f64), String> {
+    match format.to_lowercase().as_str() {
+        "letter" => Ok((8.5, 11.0)),
+        "legal" => Ok((8.5, 14.0)),
+        "tabloid" => Ok((11.0, 17.0)),
+        "a3" => Ok((11.69, 16.54)),
+        "a4" => Ok((8.27, 11.69)),
+        "a5" => Ok((5.83, 8.27)),
+        _ => Err(format!("Unknown paper format: {}", format)),
+    }
+}
+
+// Timestamped file in the user's downloads folder, for exports that were not given a path
+pub fn default_export_path(extension: &str) -> String {
+    let directory = dirs::download_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(std::env::temp_dir);
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    directory
+        .join(format!("page-{}.{}", timestamp, extension))
+        .to_string_lossy()
+        .to_string()
+}
+
+// Write base64 data to `path` if one was given, otherwise hand the data back
+pub fn save_or_return(
+    format: String,
+    data: String,
+    path: Option<String>,
+) -> Result<CaptureResult, CommandError> {
+    match path {
+        Some(path) => {
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(&data)
+                .map_err(|e| format!("Failed to decode captured data: {}", e))?;
+            std::fs::write(&path, bytes)
+                .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+            println!("Saved {} capture to {}", format, path);
+            Ok(CaptureResult {
+                format,
+                path: Some(path),
+                data: None,
+            })
+        }
+        None => Ok(CaptureResult {
+            format,
+            path: None,
+            data: Some(data),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn paper_size_knows_the_listed_formats() {
+        assert_eq!(paper_size("letter"), Ok((8.5, 11.0)));
+        assert_eq!(paper_size("legal"), Ok((8.5, 14.0)));
+        assert_eq!(paper_size("tabloid"), Ok((11.0, 17.0)));
+        assert_eq!(paper_size("a3"), Ok((11.69, 16.54)));
+        assert_eq!(paper_size("a4"), Ok((8.27, 11.69)));
+        assert_eq!(paper_size("a5"), Ok((5.83, 8.27)));
+    }
+
+    #[test]
+    fn paper_size_ignores_case() {
+        assert_eq!(paper_size("A4"), paper_size("a4"));
+        assert_eq!(paper_size("Letter"), paper_size("letter"));
+    }
+
+    #[test]
+    fn paper_size_rejects_unknown_formats() {
+        assert_eq!(
+            paper_size("b5"),
+            Err("Unknown paper format: b5".to_string())
+        );
+    }
+
+    #[test]
+    fn pdf_defaults_to_portrait_letter() {
+        let print = PdfOptions::default().print_command().unwrap();
+        assert_eq!(
+            serde_json::to_value(print).unwrap(),
+            json!({
+                "landscape": false,
+                "printBackground": false,
+                "paperWidth": 8.5,
+                "paperHeight": 11.0,
+                "marginTop": 0.4,
+                "marginBottom": 0.4,
+                "marginLeft": 0.4,
+                "marginRight": 0.4,
+            })
+        );
+    }
+
+    #[test]
+    fn pdf_explicit_size_overrides_the_format() {
+        let options = PdfOptions {
+            paper_format: Some("a4".to_string()),
+            paper_width: Some(5.0),
+            margin_left: Some(0.0),
+            landscape: Some(true),
+            print_background: Some(true),
+            ..Default::default()
+        };
+        let print = options.print_command().unwrap();
+
+        assert_eq!(print.paper_width, 5.0);
+        assert_eq!(print.paper_height, 11.69); // Still taken from A4
+        assert_eq!(print.margin_left, 0.0);
+        assert_eq!(print.margin_right, DEFAULT_PDF_MARGIN);
+        assert!(print.landscape);
+        assert!(print.print_background);
+    }
+
+    #[test]
+    fn pdf_unknown_format_fails_even_with_a_size() {
+        let options = PdfOptions {
+            paper_format: Some("b5".to_string()),
+            paper_width: Some(5.0),
+            paper_height: Some(7.0),
+            ..Default::default()
+        };
+        assert!(options.print_command().is_err());
+    }
+}
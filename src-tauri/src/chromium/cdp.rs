@@ -0,0 +1,807 @@
+use crate::chromium::protocol::{self, runtime, CdpClient};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::Duration;
+use tokio::sync::futures::Notified;
+use tokio::sync::{broadcast, mpsc, oneshot, Notify};
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+
+const CDP_EVENT_CAPACITY: usize = 512;
+
+type PendingRequests =
+    Arc<Mutex<HashMap<u32, oneshot::Sender<Result<serde_json::Value, CdpError>>>>>;
+
+// Default execution context of each frame in the target, by frame id
+type FrameContexts = Arc<Mutex<HashMap<String, i64>>>;
+
+type EventSink = Arc<Mutex<Option<mpsc::UnboundedSender<CdpEvent>>>>;
+
+// Channels for events that must not be lost to a lagging event stream, hence unbounded
+#[derive(Clone, Default)]
+pub struct EventSinks {
+    // Where Fetch.requestPaused goes once interception is on; a paused request
+    // that never reaches its handler stays paused for good
+    paused_requests: EventSink,
+    // A copy of every Network event while a HAR is recorded, which is incomplete without any
+    network_events: EventSink,
+}
+
+#[derive(Debug, Clone)]
+pub enum CdpError {
+    // The WebSocket could not be opened, or went away
+    Connection(String),
+    // No response arrived in time for this method
+    Timeout(String),
+    // Chrome answered with an error object
+    Protocol { code: i64, message: String },
+    // The evaluated script threw
+    Exception(String),
+    // Params or result did not match the typed structs
+    Serialization(String),
+    // The session's in-flight operations were cancelled
+    Cancelled(String),
+}
+
+impl CdpError {
+    // Failures that may go away on their own, such as a page navigating mid-call.
+    // A command that timed out or lost its page midway may still have run, so
+    // only idempotent ones are worth sending again
+    pub fn is_retryable(&self, idempotent: bool) -> bool {
+        match self {
+            CdpError::Timeout(_) => idempotent,
+            CdpError::Protocol { message, .. } => {
+                // Rejected before it ran
+                message.contains("Cannot find context with specified id")
+                    || (idempotent && message.contains("Execution context was destroyed"))
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for CdpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CdpError::Connection(message) => write!(f, "{}", message),
+            CdpError::Timeout(method) => write!(f, "Request timeout: {}", method),
+            CdpError::Protocol { code, message } => write!(f, "CDP Error {}: {}", code, message),
+            CdpError::Exception(text) => write!(f, "Script exception: {}", text),
+            CdpError::Serialization(message) => write!(f, "{}", message),
+            CdpError::Cancelled(method) => write!(f, "Request cancelled: {}", method),
+        }
+    }
+}
+
+impl std::error::Error for CdpError {}
+
+impl From<CdpError> for String {
+    fn from(error: CdpError) -> Self {
+        error.to_string()
+    }
+}
+
+// How long a request may wait for its response, and how often it is retried
+#[derive(Debug, Clone, Copy)]
+pub struct CallPolicy {
+    pub timeout: Duration,
+    pub retries: u32, // Extra attempts after a retryable failure
+    pub retry_delay: Duration,
+}
+
+impl Default for CallPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            retries: 0,
+            retry_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+impl CallPolicy {
+    // Whether a call that failed after `attempt` retries gets another one
+    pub fn should_retry(&self, error: &CdpError, idempotent: bool, attempt: u32) -> bool {
+        attempt < self.retries && error.is_retryable(idempotent)
+    }
+
+    pub fn with_options(self, options: &CallOptions) -> Self {
+        Self {
+            timeout: options
+                .timeout_ms
+                .map(Duration::from_millis)
+                .unwrap_or(self.timeout),
+            retries: options.retries.unwrap_or(self.retries),
+            retry_delay: self.retry_delay,
+        }
+    }
+}
+
+// Per-call overrides of the session's policy; unset fields keep the defaults
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CallOptions {
+    pub timeout_ms: Option<u64>,
+    pub retries: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CDPErrorObject {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CDPMessage {
+    id: u32,
+    method: String,
+    params: serde_json::Value,
+}
+
+// Anything Chrome sends back: either a response (with `id`) or an event (with `method`)
+#[derive(Debug, Deserialize)]
+struct CDPIncoming {
+    id: Option<u32>,
+    method: Option<String>,
+    params: Option<serde_json::Value>,
+    result: Option<serde_json::Value>,
+    error: Option<CDPErrorObject>,
+}
+
+// A CDP event such as `Page.loadEventFired`, tagged with the target that sent it
+#[derive(Debug, Clone, Serialize)]
+pub struct CdpEvent {
+    pub target_id: String,
+    pub method: String,
+    pub params: serde_json::Value,
+}
+
+impl CdpEvent {
+    pub fn domain(&self) -> &str {
+        self.method.split('.').next().unwrap_or_default()
+    }
+
+    // Id of the page a `Target.targetCreated` event announces, if it is one
+    pub fn created_page_id(&self) -> Option<&str> {
+        if self.method != "Target.targetCreated" {
+            return None;
+        }
+        let target_info = self.params.get("targetInfo")?;
+        if target_info.get("type")?.as_str()? != "page" {
+            return None;
+        }
+        target_info.get("targetId")?.as_str()
+    }
+}
+
+// A long-lived WebSocket connection to a single DevTools target.
+// Requests are matched to responses by id, so many commands can be in flight at once.
+pub struct CdpConnection {
+    websocket_url: String,
+    outgoing: mpsc::UnboundedSender<Message>,
+    pending: PendingRequests,
+    contexts: FrameContexts,
+    closed: Arc<AtomicBool>,
+    // Shared by every connection of the session, so ids are unique within it
+    message_ids: Arc<AtomicU32>,
+    policy: Arc<RwLock<CallPolicy>>,
+    cancel: Arc<Notify>,
+}
+
+impl CdpConnection {
+    pub async fn connect(
+        websocket_url: &str,
+        events: broadcast::Sender<CdpEvent>,
+        message_ids: Arc<AtomicU32>,
+        policy: Arc<RwLock<CallPolicy>>,
+        cancel: Arc<Notify>,
+        sinks: EventSinks,
+    ) -> Result<Arc<Self>, CdpError> {
+        let websocket_url = websocket_url.to_string();
+        println!("Connecting to WebSocket: {}", websocket_url);
+
+        let (ws_stream, _) = connect_async(&websocket_url)
+            .await
+            .map_err(|e| CdpError::Connection(format!("WebSocket connection failed: {}", e)))?;
+
+        let (mut write, mut read) = ws_stream.split();
+        let (outgoing, mut outgoing_rx) = mpsc::unbounded_channel::<Message>();
+        let pending = PendingRequests::default();
+        let contexts = FrameContexts::default();
+        let closed = Arc::new(AtomicBool::new(false));
+
+        // Writer: forwards queued messages until every sender is dropped
+        tokio::spawn(async move {
+            while let Some(message) = outgoing_rx.recv().await {
+                if let Err(e) = write.send(message).await {
+                    println!("Failed to send message: {}", e);
+                    break;
+                }
+            }
+            let _ = write.close().await;
+        });
+
+        // Reader: routes responses to whoever is waiting on that id, and events to subscribers
+        {
+            let pending = pending.clone();
+            let contexts = contexts.clone();
+            let closed = closed.clone();
+            let websocket_url = websocket_url.clone();
+            let target_id = target_id_from_websocket_url(&websocket_url);
+
+            tokio::spawn(async move {
+                while let Some(msg) = read.next().await {
+                    match msg {
+                        Ok(Message::Text(text)) => dispatch_incoming(
+                            &pending, &contexts, &events, &sinks, &target_id, &text,
+                        ),
+                        Ok(Message::Close(_)) => break,
+                        Ok(_) => continue,
+                        Err(e) => {
+                            println!("WebSocket error on {}: {}", websocket_url, e);
+                            break;
+                        }
+                    }
+                }
+
+                println!("WebSocket connection closed: {}", websocket_url);
+                closed.store(true, Ordering::SeqCst);
+
+                // Fail every request that was still waiting on this socket
+                for (_, tx) in pending.lock().unwrap().drain() {
+                    let _ = tx.send(Err(CdpError::Connection(
+                        "WebSocket connection closed".to_string(),
+                    )));
+                }
+            });
+        }
+
+        Ok(Arc::new(Self {
+            websocket_url,
+            outgoing,
+            pending,
+            contexts,
+            closed,
+            message_ids,
+            policy,
+            cancel,
+        }))
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    // Default execution context of a frame, known while Runtime is enabled
+    pub fn frame_context(&self, frame_id: &str) -> Option<i64> {
+        self.contexts.lock().unwrap().get(frame_id).copied()
+    }
+
+    // The session's current defaults
+    pub fn policy(&self) -> CallPolicy {
+        *self.policy.read().unwrap()
+    }
+
+    // Resolves when the session's in-flight operations are cancelled
+    pub fn cancelled(&self) -> Notified<'_> {
+        self.cancel.notified()
+    }
+
+    // Send a CDP command and wait up to `timeout` for its response
+    pub async fn send(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+        timeout: Duration,
+    ) -> Result<serde_json::Value, CdpError> {
+        // Created before sending, so a cancel issued from here on is never missed
+        let cancelled = self.cancelled();
+
+        if self.is_closed() {
+            return Err(CdpError::Connection(format!(
+                "WebSocket connection to {} is closed",
+                self.websocket_url
+            )));
+        }
+
+        let message_id = self.message_ids.fetch_add(1, Ordering::Relaxed);
+
+        let cdp_message = CDPMessage {
+            id: message_id,
+            method: method.to_string(),
+            params,
+        };
+
+        let message_json = serde_json::to_string(&cdp_message)
+            .map_err(|e| {
+                CdpError::Serialization(format!("Failed to serialize CDP message: {}", e))
+            })?;
+
+        println!("Sending CDP message: {}", message_json);
+
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(message_id, tx);
+
+        if self.outgoing.send(Message::Text(message_json)).is_err() {
+            self.pending.lock().unwrap().remove(&message_id);
+            return Err(CdpError::Connection(
+                "Failed to send message: connection closed".to_string(),
+            ));
+        }
+
+        tokio::select! {
+            response = tokio::time::timeout(timeout, rx) => match response {
+                Ok(Ok(result)) => result,
+                Ok(Err(_)) => Err(CdpError::Connection("No response received".to_string())),
+                Err(_) => {
+                    self.pending.lock().unwrap().remove(&message_id);
+                    Err(CdpError::Timeout(method.to_string()))
+                }
+            },
+            _ = cancelled => {
+                self.pending.lock().unwrap().remove(&message_id);
+                Err(CdpError::Cancelled(method.to_string()))
+            }
+        }
+    }
+}
+
+fn dispatch_incoming(
+    pending: &PendingRequests,
+    contexts: &FrameContexts,
+    events: &broadcast::Sender<CdpEvent>,
+    sinks: &EventSinks,
+    target_id: &str,
+    text: &str,
+) {
+    let incoming = match serde_json::from_str::<CDPIncoming>(text) {
+        Ok(incoming) => incoming,
+        Err(_) => return,
+    };
+
+    if let Some(id) = incoming.id {
+        if let Some(tx) = pending.lock().unwrap().remove(&id) {
+            let result = match incoming.error {
+                Some(error) => Err(CdpError::Protocol {
+                    code: error.code,
+                    message: error.message,
+                }),
+                None => Ok(incoming.result.unwrap_or(serde_json::Value::Null)),
+            };
+            let _ = tx.send(result);
+        }
+    } else if let Some(method) = incoming.method {
+        let params = incoming.params.unwrap_or(serde_json::Value::Null);
+        track_execution_context(contexts, &method, &params);
+
+        let mut event = CdpEvent {
+            target_id: target_id.to_string(),
+            method,
+            params,
+        };
+        if event.domain() == "Network" {
+            if let Some(tx) = sinks.network_events.lock().unwrap().as_ref() {
+                let _ = tx.send(event.clone());
+            }
+        }
+        if event.method == "Fetch.requestPaused" {
+            if let Some(tx) = sinks.paused_requests.lock().unwrap().as_ref() {
+                match tx.send(event) {
+                    Ok(()) => return,
+                    // The handler went away, fall back to the event stream
+                    Err(mpsc::error::SendError(unsent)) => event = unsent,
+                }
+            }
+        }
+
+        // Nobody listening is fine, the event is simply dropped
+        let _ = events.send(event);
+    }
+}
+
+// Tracked here rather than by a subscriber, so the contexts Chrome reports
+// while Runtime.enable is answered are never missed
+fn track_execution_context(contexts: &FrameContexts, method: &str, params: &serde_json::Value) {
+    match method {
+        "Runtime.executionContextCreated" => {
+            if let Ok(created) = serde_json::from_value::<runtime::ExecutionContextCreated>(params.clone()) {
+                if let Some(frame_id) = created.context.default_frame_id() {
+                    contexts
+                        .lock()
+                        .unwrap()
+                        .insert(frame_id.to_string(), created.context.id);
+                }
+            }
+        }
+        "Runtime.executionContextDestroyed" => {
+            if let Ok(destroyed) = serde_json::from_value::<runtime::ExecutionContextDestroyed>(params.clone()) {
+                contexts
+                    .lock()
+                    .unwrap()
+                    .retain(|_, id| *id != destroyed.execution_context_id);
+            }
+        }
+        "Runtime.executionContextsCleared" => contexts.lock().unwrap().clear(),
+        _ => {}
+    }
+}
+
+pub fn page_websocket_url(debug_port: u16, target_id: &str) -> String {
+    format!("ws://127.0.0.1:{}/devtools/page/{}", debug_port, target_id)
+}
+
+// ws://127.0.0.1:9222/devtools/page/<id> -> <id>
+pub fn target_id_from_websocket_url(websocket_url: &str) -> String {
+    websocket_url
+        .rsplit('/')
+        .next()
+        .unwrap_or(websocket_url)
+        .to_string()
+}
+
+// Open connections of a session, one per target WebSocket URL, plus the
+// session-wide event stream they all feed into
+#[derive(Clone, Default)]
+pub struct CdpConnectionPool {
+    inner: Arc<PoolInner>,
+}
+
+struct PoolInner {
+    connections: tokio::sync::Mutex<HashMap<String, Arc<CdpConnection>>>,
+    events: broadcast::Sender<CdpEvent>,
+    // Domains every connection of the session has enabled
+    enabled_domains: Mutex<HashSet<String>>,
+    // Domains whose events go to the frontend
+    forwarded_domains: Mutex<HashSet<String>>,
+    // Navigations under way on each target, which need its load events
+    navigations: Mutex<HashMap<String, usize>>,
+    forwarding_events: AtomicBool,
+    message_ids: Arc<AtomicU32>,
+    policy: Arc<RwLock<CallPolicy>>,
+    // Wakes every request of the session that is waiting on a response
+    cancel: Arc<Notify>,
+    sinks: EventSinks,
+}
+
+impl Default for PoolInner {
+    fn default() -> Self {
+        let (events, _) = broadcast::channel(CDP_EVENT_CAPACITY);
+        Self {
+            connections: tokio::sync::Mutex::new(HashMap::new()),
+            events,
+            enabled_domains: Mutex::new(HashSet::new()),
+            forwarded_domains: Mutex::new(HashSet::new()),
+            navigations: Mutex::new(HashMap::new()),
+            forwarding_events: AtomicBool::new(false),
+            message_ids: Arc::new(AtomicU32::new(1)),
+            policy: Arc::default(),
+            cancel: Arc::default(),
+            sinks: EventSinks::default(),
+        }
+    }
+}
+
+impl CdpConnectionPool {
+    // Reuse the open connection to this target, or connect if there is none
+    pub async fn client(&self, websocket_url: &str) -> Result<CdpClient, CdpError> {
+        let mut connections = self.inner.connections.lock().await;
+
+        // Drop connections of targets that have gone away
+        connections.retain(|_, connection| !connection.is_closed());
+
+        if let Some(connection) = connections.get(websocket_url) {
+            return Ok(CdpClient::new(connection.clone()));
+        }
+
+        let connection = CdpConnection::connect(
+            websocket_url,
+            self.inner.events.clone(),
+            self.inner.message_ids.clone(),
+            self.inner.policy.clone(),
+            self.inner.cancel.clone(),
+            self.inner.sinks.clone(),
+        )
+        .await?;
+        let client = CdpClient::new(connection.clone());
+
+        // New targets report the same domains as the rest of the session. Target discovery
+        // is browser-wide, enabling it on pages too would only duplicate its events, and
+        // request interception only belongs on pages, where the rules handler continues them
+        let is_browser = websocket_url.contains("/devtools/browser/");
+        for domain in self.enabled_domains() {
+            if (domain == "Target" && !is_browser) || (domain == "Fetch" && is_browser) {
+                continue;
+            }
+            if let Err(e) = protocol::enable_domain(&client, &domain).await {
+                println!("Failed to enable {} on {}: {}", domain, websocket_url, e);
+            }
+        }
+
+        connections.insert(websocket_url.to_string(), connection);
+        Ok(client)
+    }
+
+    // Enable a domain on the given target and remember it for future connections
+    pub async fn enable_domain(&self, client: &CdpClient, domain: &str) -> Result<(), CdpError> {
+        protocol::enable_domain(client, domain).await?;
+        self.inner
+            .enabled_domains
+            .lock()
+            .unwrap()
+            .insert(domain.to_string());
+        Ok(())
+    }
+
+    // Disable a domain on every open connection and stop enabling it on new ones
+    pub async fn disable_domain(&self, domain: &str) {
+        self.inner.enabled_domains.lock().unwrap().remove(domain);
+
+        let connections = self.inner.connections.lock().await;
+        for (websocket_url, connection) in connections.iter() {
+            let client = CdpClient::new(connection.clone());
+            if let Err(e) = protocol::disable_domain(&client, domain).await {
+                println!("Failed to disable {} on {}: {}", domain, websocket_url, e);
+            }
+        }
+    }
+
+    // Defaults for every call of the session, including clients already handed out
+    pub fn set_call_policy(&self, policy: CallPolicy) {
+        *self.inner.policy.write().unwrap() = policy;
+    }
+
+    // Abort the requests currently waiting on a response. Later requests are unaffected
+    pub fn cancel(&self) {
+        self.inner.cancel.notify_waiters();
+    }
+
+    pub fn enabled_domains(&self) -> Vec<String> {
+        self.inner
+            .enabled_domains
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    // Count a navigation of the target until finish_navigation
+    pub fn start_navigation(&self, target_id: &str) {
+        *self
+            .inner
+            .navigations
+            .lock()
+            .unwrap()
+            .entry(target_id.to_string())
+            .or_default() += 1;
+    }
+
+    // Returns true when no other navigation of the target is still waiting on its events
+    pub fn finish_navigation(&self, target_id: &str) -> bool {
+        let mut navigations = self.inner.navigations.lock().unwrap();
+        match navigations.get_mut(target_id) {
+            Some(count) if *count > 1 => {
+                *count -= 1;
+                false
+            }
+            _ => {
+                navigations.remove(target_id);
+                true
+            }
+        }
+    }
+
+    pub fn forward_domain(&self, domain: &str) {
+        self.inner
+            .forwarded_domains
+            .lock()
+            .unwrap()
+            .insert(domain.to_string());
+    }
+
+    pub fn stop_forwarding_domain(&self, domain: &str) {
+        self.inner.forwarded_domains.lock().unwrap().remove(domain);
+    }
+
+    pub fn forwarded_domains(&self) -> Vec<String> {
+        self.inner
+            .forwarded_domains
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    pub fn is_forwarded(&self, domain: &str) -> bool {
+        self.inner.forwarded_domains.lock().unwrap().contains(domain)
+    }
+
+    pub fn subscribe_events(&self) -> broadcast::Receiver<CdpEvent> {
+        self.inner.events.subscribe()
+    }
+
+    // Route every Fetch.requestPaused of the session to the returned receiver instead
+    // of the lossy event stream. A later call replaces the earlier receiver
+    pub fn paused_requests(&self) -> mpsc::UnboundedReceiver<CdpEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        *self.inner.sinks.paused_requests.lock().unwrap() = Some(tx);
+        rx
+    }
+
+    // Copy every Network event of the session to the returned receiver as well,
+    // until stop_network_events. A later call replaces the earlier receiver
+    pub fn network_events(&self) -> mpsc::UnboundedReceiver<CdpEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        *self.inner.sinks.network_events.lock().unwrap() = Some(tx);
+        rx
+    }
+
+    pub fn stop_network_events(&self) {
+        self.inner.sinks.network_events.lock().unwrap().take();
+    }
+
+    // Returns true only for the first caller, so a session gets a single forwarder
+    pub fn start_forwarding_events(&self) -> bool {
+        !self.inner.forwarding_events.swap(true, Ordering::SeqCst)
+    }
+
+    pub fn downgrade(&self) -> WeakCdpConnectionPool {
+        WeakCdpConnectionPool(Arc::downgrade(&self.inner))
+    }
+}
+
+impl fmt::Debug for CdpConnectionPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CdpConnectionPool").finish_non_exhaustive()
+    }
+}
+
+// Lets background tasks hold on to a pool without keeping the session alive
+#[derive(Clone)]
+pub struct WeakCdpConnectionPool(Weak<PoolInner>);
+
+impl WeakCdpConnectionPool {
+    pub fn upgrade(&self) -> Option<CdpConnectionPool> {
+        self.0.upgrade().map(|inner| CdpConnectionPool { inner })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn protocol_error(message: &str) -> CdpError {
+        CdpError::Protocol {
+            code: -32000,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn timeouts_are_retried_only_for_idempotent_calls() {
+        let timeout = CdpError::Timeout("Page.getFrameTree".to_string());
+        assert!(timeout.is_retryable(true));
+        assert!(!timeout.is_retryable(false));
+    }
+
+    #[test]
+    fn lost_contexts_are_retried_when_the_call_never_ran() {
+        let not_found = protocol_error("Cannot find context with specified id");
+        assert!(not_found.is_retryable(true));
+        assert!(not_found.is_retryable(false));
+
+        // The script may have run partly before its page navigated away
+        let destroyed = protocol_error("Execution context was destroyed.");
+        assert!(destroyed.is_retryable(true));
+        assert!(!destroyed.is_retryable(false));
+    }
+
+    #[test]
+    fn other_failures_are_not_retried() {
+        for error in [
+            CdpError::Connection("WebSocket connection closed".to_string()),
+            protocol_error("No node with given id found"),
+            CdpError::Exception("ReferenceError: x is not defined".to_string()),
+            CdpError::Serialization("Failed to parse result".to_string()),
+            CdpError::Cancelled("Runtime.evaluate".to_string()),
+        ] {
+            assert!(!error.is_retryable(true), "{} was retryable", error);
+        }
+    }
+
+    #[test]
+    fn retries_stop_after_the_policy_limit() {
+        let policy = CallPolicy {
+            retries: 2,
+            ..Default::default()
+        };
+        let timeout = CdpError::Timeout("DOM.getBoxModel".to_string());
+
+        assert!(policy.should_retry(&timeout, true, 0));
+        assert!(policy.should_retry(&timeout, true, 1));
+        assert!(!policy.should_retry(&timeout, true, 2));
+        assert!(!policy.should_retry(&timeout, false, 0));
+    }
+
+    #[test]
+    fn default_policy_does_not_retry() {
+        let timeout = CdpError::Timeout("Page.getFrameTree".to_string());
+        assert!(!CallPolicy::default().should_retry(&timeout, true, 0));
+    }
+
+    #[test]
+    fn call_options_override_the_session_policy() {
+        let policy = CallPolicy {
+            timeout: Duration::from_secs(10),
+            retries: 2,
+            retry_delay: Duration::from_millis(500),
+        };
+
+        let overridden = policy.with_options(&CallOptions {
+            timeout_ms: Some(1_500),
+            retries: Some(0),
+        });
+        assert_eq!(overridden.timeout, Duration::from_millis(1_500));
+        assert_eq!(overridden.retries, 0);
+        assert_eq!(overridden.retry_delay, Duration::from_millis(500));
+
+        let unchanged = policy.with_options(&CallOptions::default());
+        assert_eq!(unchanged.timeout, policy.timeout);
+        assert_eq!(unchanged.retries, policy.retries);
+    }
+
+    #[test]
+    fn target_id_is_the_last_path_segment() {
+        assert_eq!(
+            target_id_from_websocket_url("ws://127.0.0.1:9222/devtools/page/ABC123"),
+            "ABC123"
+        );
+        assert_eq!(
+            target_id_from_websocket_url(&page_websocket_url(9333, "XYZ")),
+            "XYZ"
+        );
+    }
+
+    #[test]
+    fn only_the_last_navigation_of_a_target_finishes_it() {
+        let pool = CdpConnectionPool::default();
+        pool.start_navigation("A");
+        pool.start_navigation("A");
+        pool.start_navigation("B");
+
+        assert!(!pool.finish_navigation("A"));
+        assert!(pool.finish_navigation("B"));
+        assert!(pool.finish_navigation("A"));
+    }
+
+    #[test]
+    fn network_events_are_copied_to_their_sink() {
+        let (events, mut stream) = broadcast::channel(8);
+        let sinks = EventSinks::default();
+        let (tx, mut network_events) = mpsc::unbounded_channel();
+        *sinks.network_events.lock().unwrap() = Some(tx);
+
+        for method in ["Network.dataReceived", "Page.loadEventFired"] {
+            let text = serde_json::json!({ "method": method, "params": {} }).to_string();
+            dispatch_incoming(
+                &PendingRequests::default(),
+                &FrameContexts::default(),
+                &events,
+                &sinks,
+                "T1",
+                &text,
+            );
+        }
+
+        assert_eq!(
+            network_events.try_recv().unwrap().method,
+            "Network.dataReceived"
+        );
+        assert!(network_events.try_recv().is_err());
+        assert_eq!(stream.try_recv().unwrap().method, "Network.dataReceived");
+        assert_eq!(stream.try_recv().unwrap().method, "Page.loadEventFired");
+    }
+}
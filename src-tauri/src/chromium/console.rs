@@ -0,0 +1,274 @@
+use crate::chromium::cdp::{CdpConnectionPool, CdpEvent};
+use crate::chromium::lib::{check_chrome_devtools, ChromeSession};
+use crate::chromium::protocol::log::EntryAdded;
+use crate::chromium::protocol::runtime::{ConsoleApiCalled, ExceptionThrown, RemoteObject};
+use crate::chromium::sessions::SessionRegistry;
+use crate::error::CommandError;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::broadcast::error::RecvError;
+
+// Name of the Tauri event live console entries are emitted under
+pub const CHROME_CONSOLE_EVENT: &str = "chrome-console-log";
+
+// Oldest entries are dropped once a session holds this many
+const CONSOLE_BUFFER_CAPACITY: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsoleEntry {
+    pub target_id: String,
+    pub source: String, // "console", "exception" or the Log domain source ("network", ...)
+    pub level: String,  // "debug", "log", "info", "warning" or "error"
+    pub text: String,
+    pub url: Option<String>,
+    pub line_number: Option<i64>,
+    pub timestamp: f64, // Milliseconds since the epoch
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ConsoleLogFilter {
+    pub levels: Option<Vec<String>>,
+    pub target_id: Option<String>,
+    pub since: Option<f64>, // Milliseconds since the epoch, inclusive
+    pub until: Option<f64>,
+    pub limit: Option<usize>, // Most recent entries win
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConsoleEventPayload {
+    pub session_id: String,
+    pub entry: ConsoleEntry,
+}
+
+// Recent console output of a session, plus where to stream new entries
+#[derive(Debug, Clone, Default)]
+pub struct ConsoleBuffer {
+    entries: Arc<Mutex<VecDeque<ConsoleEntry>>>,
+    stream: Arc<Mutex<Option<AppHandle>>>,
+}
+
+impl ConsoleBuffer {
+    fn push(&self, session_id: &str, entry: ConsoleEntry) {
+        if let Some(app) = self.stream.lock().unwrap().as_ref() {
+            let payload = ConsoleEventPayload {
+                session_id: session_id.to_string(),
+                entry: entry.clone(),
+            };
+            if let Err(e) = app.emit(CHROME_CONSOLE_EVENT, payload) {
+                println!("Failed to emit console entry: {}", e);
+            }
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == CONSOLE_BUFFER_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    fn query(&self, filter: &ConsoleLogFilter) -> Vec<ConsoleEntry> {
+        let mut entries: Vec<ConsoleEntry> = self
+            .entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|entry| {
+                filter
+                    .levels
+                    .as_ref()
+                    .is_none_or(|levels| levels.iter().any(|l| l.eq_ignore_ascii_case(&entry.level)))
+                    && filter.target_id.as_ref().is_none_or(|id| id == &entry.target_id)
+                    && filter.since.is_none_or(|since| entry.timestamp >= since)
+                    && filter.until.is_none_or(|until| entry.timestamp <= until)
+            })
+            .cloned()
+            .collect();
+
+        if let Some(limit) = filter.limit {
+            let skip = entries.len().saturating_sub(limit);
+            entries.drain(..skip);
+        }
+        entries
+    }
+}
+
+#[tauri::command]
+pub fn chrome_get_console_logs(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    filter: Option<ConsoleLogFilter>,
+) -> Result<Vec<ConsoleEntry>, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    Ok(session.console.query(&filter.unwrap_or_default()))
+}
+
+#[tauri::command]
+pub fn chrome_clear_console_logs(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+) -> Result<(), CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    session.console.entries.lock().unwrap().clear();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn chrome_stream_console_logs(
+    app: AppHandle,
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    enabled: bool,
+) -> Result<(), CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    *session.console.stream.lock().unwrap() = enabled.then_some(app);
+    Ok(())
+}
+
+// Collect console calls, uncaught exceptions and browser log entries for as
+// long as the session exists
+pub fn spawn_console_capture(session: &ChromeSession) {
+    let mut events = session.connections.subscribe_events();
+    let pool = session.connections.downgrade();
+    let console = session.console.clone();
+    let debug_port = session.debug_port;
+    let session_id = session.session_id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        match pool.upgrade() {
+            Some(pool) => {
+                if let Err(e) = enable_console_domains(&pool, debug_port).await {
+                    println!("Console capture unavailable for session {}: {}", session_id, e);
+                    return;
+                }
+            }
+            None => return,
+        }
+
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    println!("Console capture for session {} skipped {} events", session_id, skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+
+            if let Some(entry) = console_entry(event) {
+                console.push(&session_id, entry);
+            }
+        }
+    });
+}
+
+// Enabled on the pool, so tabs opened later report console output as soon as they are attached
+async fn enable_console_domains(pool: &CdpConnectionPool, debug_port: u16) -> Result<(), String> {
+    let targets = check_chrome_devtools(debug_port).await?;
+
+    // One tab that can't be reached shouldn't keep the others from reporting
+    for target in targets.iter().filter(|t| t.target_type == "page") {
+        let client = match pool.client(&target.websocket_debugger_url).await {
+            Ok(client) => client,
+            Err(e) => {
+                println!("Console capture unavailable for {}: {}", target.url, e);
+                continue;
+            }
+        };
+        for domain in ["Runtime", "Log"] {
+            if let Err(e) = pool.enable_domain(&client, domain).await {
+                println!("Failed to enable {} on {}: {}", domain, target.url, e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Console entry the event reports, if any. Malformed events are logged and skipped
+fn console_entry(event: CdpEvent) -> Option<ConsoleEntry> {
+    let entry = match event.method.as_str() {
+        "Runtime.consoleAPICalled" => {
+            let params: ConsoleApiCalled = event_params(&event.method, event.params)?;
+            let frame = params
+                .stack_trace
+                .as_ref()
+                .and_then(|trace| trace.call_frames.first());
+
+            ConsoleEntry {
+                target_id: event.target_id,
+                source: "console".to_string(),
+                level: console_level(&params.call_type).to_string(),
+                text: params.args.iter().map(describe).collect::<Vec<_>>().join(" "),
+                url: frame.map(|f| f.url.clone()),
+                line_number: frame.map(|f| f.line_number),
+                timestamp: params.timestamp,
+            }
+        }
+        "Runtime.exceptionThrown" => {
+            let params: ExceptionThrown = event_params(&event.method, event.params)?;
+            let details = params.exception_details;
+
+            ConsoleEntry {
+                target_id: event.target_id,
+                source: "exception".to_string(),
+                level: "error".to_string(),
+                text: details.message(),
+                url: details.url,
+                line_number: Some(details.line_number),
+                timestamp: params.timestamp,
+            }
+        }
+        "Log.entryAdded" => {
+            let entry = event_params::<EntryAdded>(&event.method, event.params)?.entry;
+
+            ConsoleEntry {
+                target_id: event.target_id,
+                source: entry.source,
+                level: console_level(&entry.level).to_string(),
+                text: entry.text,
+                url: entry.url,
+                line_number: entry.line_number,
+                timestamp: entry.timestamp,
+            }
+        }
+        _ => return None,
+    };
+
+    Some(entry)
+}
+
+fn event_params<T: DeserializeOwned>(method: &str, params: serde_json::Value) -> Option<T> {
+    match serde_json::from_value(params) {
+        Ok(params) => Some(params),
+        Err(e) => {
+            println!("Skipping malformed {} event: {}", method, e);
+            None
+        }
+    }
+}
+
+// Fold console call types and Log levels into one set of levels
+fn console_level(kind: &str) -> &'static str {
+    match kind {
+        "error" | "assert" => "error",
+        "warning" => "warning",
+        "info" => "info",
+        "debug" | "verbose" => "debug",
+        _ => "log",
+    }
+}
+
+fn describe(arg: &RemoteObject) -> String {
+    match (&arg.value, &arg.description) {
+        (Some(serde_json::Value::String(text)), _) => text.clone(),
+        (Some(value), _) => value.to_string(),
+        (None, Some(description)) => description.clone(),
+        (None, None) => "undefined".to_string(),
+    }
+}
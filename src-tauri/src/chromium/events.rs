@@ -0,0 +1,146 @@
+use crate::chromium::cdp::page_websocket_url;
+use crate::chromium::lib::{check_chrome_devtools, fetch_browser_version, ChromeSession};
+use crate::chromium::sessions::SessionRegistry;
+use crate::error::CommandError;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::broadcast::error::RecvError;
+
+// Name of the Tauri event every forwarded CDP event is emitted under
+pub const CHROME_CDP_EVENT: &str = "chrome-cdp-event";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChromeEventPayload {
+    pub session_id: String,
+    pub target_id: String,
+    pub method: String,
+    pub params: serde_json::Value,
+}
+
+#[tauri::command]
+pub async fn chrome_subscribe_events(
+    app: AppHandle,
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    domains: Vec<String>,
+) -> Result<Vec<String>, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let targets = check_chrome_devtools(session.debug_port)
+        .await
+        .map_err(|e| format!("Chrome session is no longer responsive: {}", e))?;
+
+    // Fetch pauses every request until something continues it, which only the
+    // network rules handler does
+    if domains.iter().any(|domain| domain == "Fetch") {
+        return Err(
+            "Fetch events can't be subscribed to, add a network rule to intercept requests".into(),
+        );
+    }
+
+    for domain in &domains {
+        if domain == "Target" {
+            // Target discovery is browser-wide, so it goes through the browser endpoint
+            let browser = fetch_browser_version(session.debug_port).await?;
+            let client = session
+                .connections
+                .client(&browser.websocket_debugger_url)
+                .await?;
+            session.connections.enable_domain(&client, domain).await?;
+            session.connections.forward_domain(domain);
+            continue;
+        }
+
+        for target in targets.iter().filter(|t| t.target_type == "page") {
+            let client = session
+                .connections
+                .client(&target.websocket_debugger_url)
+                .await?;
+            session
+                .connections
+                .enable_domain(&client, domain)
+                .await
+                .map_err(|e| format!("Failed to enable {} events: {}", domain, e))?;
+        }
+        session.connections.forward_domain(domain);
+    }
+
+    if session.connections.start_forwarding_events() {
+        spawn_event_forwarder(app, &session);
+    }
+
+    Ok(session.connections.forwarded_domains())
+}
+
+#[tauri::command]
+pub fn chrome_unsubscribe_events(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    domains: Vec<String>,
+) -> Result<Vec<String>, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    // Chrome keeps sending the events, they just stop being forwarded
+    for domain in &domains {
+        session.connections.stop_forwarding_domain(domain);
+    }
+
+    Ok(session.connections.forwarded_domains())
+}
+
+// Forward the session's CDP events to the frontend for as long as the session exists
+fn spawn_event_forwarder(app: AppHandle, session: &ChromeSession) {
+    let mut events = session.connections.subscribe_events();
+    let pool = session.connections.downgrade();
+    let session_id = session.session_id.clone();
+    let debug_port = session.debug_port;
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    println!(
+                        "Event forwarder for session {} skipped {} events",
+                        session_id, skipped
+                    );
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+
+            let pool = match pool.upgrade() {
+                Some(pool) => pool,
+                None => break,
+            };
+
+            // Attach to new tabs so they report the subscribed domains too
+            if let Some(target_id) = event.created_page_id() {
+                let websocket_url = page_websocket_url(debug_port, target_id);
+                let pool = pool.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = pool.client(&websocket_url).await {
+                        println!("Failed to attach to new target {}: {}", websocket_url, e);
+                    }
+                });
+            }
+
+            if !pool.is_forwarded(event.domain()) {
+                continue;
+            }
+
+            let payload = ChromeEventPayload {
+                session_id: session_id.clone(),
+                target_id: event.target_id,
+                method: event.method,
+                params: event.params,
+            };
+
+            if let Err(e) = app.emit(CHROME_CDP_EVENT, payload) {
+                println!("Failed to emit Chrome event: {}", e);
+            }
+        }
+
+        println!("Event forwarder for session {} stopped", session_id);
+    });
+}
@@ -0,0 +1,249 @@
+use crate::chromium::lib::{check_chrome_devtools, ChromeSession, ChromeTarget};
+use crate::chromium::protocol::{dom, page, CdpClient};
+use crate::error::CommandError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+// A frame of the page that scripts can run in
+#[derive(Clone)]
+pub struct PageFrame {
+    pub frame_id: String,
+    pub url: String,
+    pub context_id: Option<i64>, // None for the main frame of its target, which is the default
+    pub target: Option<Arc<IframeTarget>>, // Cross-site iframe the frame is in; None for the page
+}
+
+// A cross-site iframe, which with site isolation runs in its own process as a
+// target of its own. Its frame id is the target id
+pub struct IframeTarget {
+    client: CdpClient,
+    frame_id: String,
+    parent: Option<Arc<IframeTarget>>, // None when the iframe is in the page itself
+}
+
+// Frames of the page, and the ones left out
+pub struct PageFrames {
+    pub frames: Vec<PageFrame>,
+    pub skipped: Vec<SkippedFrame>,
+}
+
+// A frame scripts could not run in, and why
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedFrame {
+    pub frame_id: String,
+    pub url: String,
+    pub reason: String,
+}
+
+impl SkippedFrame {
+    pub fn new(frame_id: &str, url: &str, reason: impl std::fmt::Display) -> Self {
+        Self {
+            frame_id: frame_id.to_string(),
+            url: url.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for SkippedFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "frame {} ({}): {}", self.frame_id, self.url, self.reason)
+    }
+}
+
+impl PageFrame {
+    pub fn is_main(&self) -> bool {
+        self.context_id.is_none() && self.target.is_none()
+    }
+
+    // Client that runs scripts in this frame, given the page's
+    pub fn client<'a>(&'a self, page: &'a CdpClient) -> &'a CdpClient {
+        self.target.as_ref().map_or(page, |target| &target.client)
+    }
+}
+
+// Frames of the target, main frame first, then its iframes in document order,
+// then the frames of cross-site iframes, which are reached through their own targets.
+// Frames that can't be reached are left out and reported in `skipped`.
+pub async fn page_frames(
+    session: &ChromeSession,
+    client: &CdpClient,
+) -> Result<PageFrames, CommandError> {
+    // Contexts are reported by Runtime, which the console capture normally enabled already
+    session.connections.enable_domain(client, "Runtime").await?;
+
+    let mut frames = PageFrames {
+        frames: Vec::new(),
+        skipped: Vec::new(),
+    };
+    let page_id = frames.add_target(client, None).await?;
+
+    let iframe_targets: Vec<ChromeTarget> = check_chrome_devtools(session.debug_port)
+        .await?
+        .into_iter()
+        .filter(|target| target.target_type == "iframe")
+        .collect();
+
+    // Cross-site iframes, each under the page or iframe target it is embedded in
+    let mut parents: Vec<(String, Option<Arc<IframeTarget>>)> = vec![(page_id, None)];
+    while let Some((parent_id, parent)) = parents.pop() {
+        for target in iframe_targets
+            .iter()
+            .filter(|target| target.parent_id.as_deref() == Some(parent_id.as_str()))
+        {
+            let iframe = match session
+                .connections
+                .client(&target.websocket_debugger_url)
+                .await
+            {
+                Ok(iframe_client) => Arc::new(IframeTarget {
+                    client: iframe_client.with_options(Some(client.options().clone())),
+                    frame_id: target.id.clone(),
+                    parent: parent.clone(),
+                }),
+                Err(e) => {
+                    frames.skipped.push(SkippedFrame::new(
+                        &target.id,
+                        &target.url,
+                        format!("Failed to attach to the cross-site iframe: {}", e),
+                    ));
+                    continue;
+                }
+            };
+
+            let added = match session
+                .connections
+                .enable_domain(&iframe.client, "Runtime")
+                .await
+            {
+                Ok(()) => {
+                    frames
+                        .add_target(&iframe.client, Some(iframe.clone()))
+                        .await
+                }
+                Err(e) => Err(e.into()),
+            };
+            match added {
+                Ok(frame_id) => parents.push((frame_id, Some(iframe))),
+                Err(e) => frames.skipped.push(SkippedFrame::new(
+                    &target.id,
+                    &target.url,
+                    format!("Failed to read the cross-site iframe: {}", e),
+                )),
+            }
+        }
+    }
+
+    // A cross-site iframe may also be listed in its parent's frame tree, without a context there
+    let reached: HashSet<&str> = frames.frames.iter().map(|f| f.frame_id.as_str()).collect();
+    frames
+        .skipped
+        .retain(|skipped| !reached.contains(skipped.frame_id.as_str()));
+    for skipped in &frames.skipped {
+        println!("Skipping {}", skipped);
+    }
+    Ok(frames)
+}
+
+impl PageFrames {
+    // The frame, or an error saying why it can't be used
+    pub fn find(&self, frame_id: &str) -> Result<&PageFrame, CommandError> {
+        if let Some(frame) = self.frames.iter().find(|frame| frame.frame_id == frame_id) {
+            return Ok(frame);
+        }
+        match self
+            .skipped
+            .iter()
+            .find(|skipped| skipped.frame_id == frame_id)
+        {
+            Some(skipped) => Err(format!("Can't use {}", skipped).into()),
+            None => Err(format!("Frame {} is no longer in the page", frame_id).into()),
+        }
+    }
+
+    // Add the frames of one target, main frame first. Returns the main frame's id
+    async fn add_target(
+        &mut self,
+        client: &CdpClient,
+        target: Option<Arc<IframeTarget>>,
+    ) -> Result<String, CommandError> {
+        let tree = client.execute(page::GetFrameTree {}).await?.frame_tree;
+        let main_id = tree.frame.id.clone();
+        self.frames.push(PageFrame {
+            frame_id: tree.frame.id,
+            url: tree.frame.url,
+            context_id: None,
+            target: target.clone(),
+        });
+
+        let mut pending: Vec<page::FrameTree> = tree.child_frames.into_iter().rev().collect();
+        while let Some(tree) = pending.pop() {
+            match client.frame_context(&tree.frame.id) {
+                Some(context_id) => self.frames.push(PageFrame {
+                    frame_id: tree.frame.id,
+                    url: tree.frame.url,
+                    context_id: Some(context_id),
+                    target: target.clone(),
+                }),
+                None => self.skipped.push(SkippedFrame::new(
+                    &tree.frame.id,
+                    &tree.frame.url,
+                    "No execution context",
+                )),
+            }
+            // Reversed so children come off the stack in document order
+            pending.extend(tree.child_frames.into_iter().rev());
+        }
+
+        Ok(main_id)
+    }
+}
+
+// Where the frame's viewport starts, in the top-level viewport
+pub async fn frame_origin(
+    client: &CdpClient,
+    frame: &PageFrame,
+) -> Result<(f64, f64), CommandError> {
+    // Cross-site iframes add up the position of each iframe up to the page
+    let (mut x, mut y) = (0.0, 0.0);
+    let mut iframe = frame.target.as_deref();
+    while let Some(target) = iframe {
+        let parent_client = target
+            .parent
+            .as_ref()
+            .map_or(client, |parent| &parent.client);
+        let (owner_x, owner_y) = owner_origin(parent_client, &target.frame_id).await?;
+        x += owner_x;
+        y += owner_y;
+        iframe = target.parent.as_deref();
+    }
+
+    if frame.context_id.is_none() {
+        return Ok((x, y));
+    }
+    let (owner_x, owner_y) = owner_origin(frame.client(client), &frame.frame_id).await?;
+    Ok((x + owner_x, y + owner_y))
+}
+
+// Top left of the content box of the iframe element showing the frame, in the
+// viewport of the target the element is in
+async fn owner_origin(client: &CdpClient, frame_id: &str) -> Result<(f64, f64), CommandError> {
+    let owner = client
+        .execute(dom::GetFrameOwner {
+            frame_id: frame_id.to_string(),
+        })
+        .await?;
+    let model = client
+        .execute(dom::GetBoxModel {
+            backend_node_id: owner.backend_node_id,
+        })
+        .await?
+        .model;
+
+    // The frame's document starts at the top left of the iframe's content box
+    match model.content.as_slice() {
+        [x, y, ..] => Ok((*x, *y)),
+        _ => Err(format!("No layout for the iframe of frame {}", frame_id).into()),
+    }
+}
@@ -0,0 +1,840 @@
+use crate::chromium::capture::default_export_path;
+use crate::chromium::cdp::CdpEvent;
+use crate::chromium::lib::{check_chrome_devtools, ChromeSession};
+use crate::chromium::protocol::network::{
+    DataReceived, LoadingFailed, LoadingFinished, Request, RequestWillBeSent,
+    RequestWillBeSentExtraInfo, Response, ResponseReceived,
+};
+use crate::chromium::sessions::SessionRegistry;
+use crate::error::CommandError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+use tokio::sync::oneshot;
+
+// Network activity of a session, collected while a recording is running
+#[derive(Debug, Clone, Default)]
+pub struct HarRecorder(Arc<Mutex<Option<HarRecording>>>);
+
+#[derive(Debug)]
+struct HarRecording {
+    entries: Vec<PendingEntry>,
+    in_flight: HashMap<String, usize>, // "<target id>:<request id>" -> index into `entries`
+    // Sent headers that arrived ahead of their request, by the same key as `in_flight`
+    early_extra_headers: HashMap<String, HashMap<String, String>>,
+    // Dropping the recording drops this sender, which stops the collector task
+    _stop: oneshot::Sender<()>,
+}
+
+#[derive(Debug)]
+struct PendingEntry {
+    request: Request,
+    wall_time: f64,
+    timestamp: f64,
+    response: Option<Response>,
+    response_timestamp: Option<f64>,
+    end_timestamp: Option<f64>,
+    data_length: i64,
+    encoded_data_length: Option<f64>,
+    redirect_url: String,
+    error: Option<String>,
+    has_extra_headers: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HarSummary {
+    pub path: String,
+    pub entry_count: usize,
+}
+
+// HAR 1.2 document, see http://www.softwareishard.com/blog/har-12-spec/
+#[derive(Debug, Serialize)]
+struct Har {
+    log: HarLog,
+}
+
+#[derive(Debug, Serialize)]
+struct HarLog {
+    version: &'static str,
+    creator: HarCreator,
+    entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct HarCreator {
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HarEntry {
+    started_date_time: String,
+    time: f64,
+    request: HarRequest,
+    response: HarResponse,
+    cache: HarCache,
+    timings: HarTimings,
+    #[serde(rename = "serverIPAddress", skip_serializing_if = "Option::is_none")]
+    server_ip_address: Option<String>,
+    #[serde(rename = "_error", skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HarRequest {
+    method: String,
+    url: String,
+    http_version: String,
+    cookies: Vec<HarNameValue>,
+    headers: Vec<HarNameValue>,
+    query_string: Vec<HarNameValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    post_data: Option<HarPostData>,
+    headers_size: i64,
+    body_size: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HarResponse {
+    status: i64,
+    status_text: String,
+    http_version: String,
+    cookies: Vec<HarNameValue>,
+    headers: Vec<HarNameValue>,
+    content: HarContent,
+    #[serde(rename = "redirectURL")]
+    redirect_url: String,
+    headers_size: i64,
+    body_size: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct HarNameValue {
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HarPostData {
+    mime_type: String,
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HarContent {
+    size: i64,
+    mime_type: String,
+}
+
+#[derive(Debug, Serialize)]
+struct HarCache {}
+
+// Milliseconds, -1 for phases that do not apply
+#[derive(Debug, Serialize)]
+struct HarTimings {
+    blocked: f64,
+    dns: f64,
+    connect: f64,
+    send: f64,
+    wait: f64,
+    receive: f64,
+    ssl: f64,
+}
+
+impl HarRecorder {
+    fn is_recording(&self) -> bool {
+        self.0.lock().unwrap().is_some()
+    }
+
+    fn record(&self, event: CdpEvent) {
+        let mut recording = self.0.lock().unwrap();
+        let recording = match recording.as_mut() {
+            Some(recording) => recording,
+            None => return,
+        };
+
+        let result = match event.method.as_str() {
+            "Network.requestWillBeSent" => serde_json::from_value(event.params)
+                .map(|params| recording.request_will_be_sent(&event.target_id, params)),
+            "Network.requestWillBeSentExtraInfo" => serde_json::from_value(event.params)
+                .map(|params| recording.request_extra_info(&event.target_id, params)),
+            "Network.responseReceived" => serde_json::from_value(event.params)
+                .map(|params| recording.response_received(&event.target_id, params)),
+            "Network.dataReceived" => serde_json::from_value(event.params)
+                .map(|params| recording.data_received(&event.target_id, params)),
+            "Network.loadingFinished" => serde_json::from_value(event.params)
+                .map(|params| recording.loading_finished(&event.target_id, params)),
+            "Network.loadingFailed" => serde_json::from_value(event.params)
+                .map(|params| recording.loading_failed(&event.target_id, params)),
+            _ => Ok(()),
+        };
+
+        if let Err(e) = result {
+            println!("Failed to record {}: {}", event.method, e);
+        }
+    }
+}
+
+impl HarRecording {
+    fn new(stop: oneshot::Sender<()>) -> Self {
+        Self {
+            entries: Vec::new(),
+            in_flight: HashMap::new(),
+            early_extra_headers: HashMap::new(),
+            _stop: stop,
+        }
+    }
+
+    fn pending(&mut self, target_id: &str, request_id: &str) -> Option<&mut PendingEntry> {
+        let index = *self.in_flight.get(&format!("{}:{}", target_id, request_id))?;
+        self.entries.get_mut(index)
+    }
+
+    fn finish(&mut self, target_id: &str, request_id: &str) {
+        self.in_flight.remove(&format!("{}:{}", target_id, request_id));
+    }
+
+    fn request_will_be_sent(&mut self, target_id: &str, params: RequestWillBeSent) {
+        // A redirect reuses the request id, so the previous hop ends here as its own entry
+        if let Some(redirect_response) = params.redirect_response {
+            if let Some(entry) = self.pending(target_id, &params.request_id) {
+                entry.encoded_data_length = redirect_response.encoded_data_length;
+                entry.response = Some(redirect_response);
+                entry.response_timestamp = Some(params.timestamp);
+                entry.end_timestamp = Some(params.timestamp);
+                entry.redirect_url = params.request.url.clone();
+            }
+            self.finish(target_id, &params.request_id);
+        }
+
+        let key = format!("{}:{}", target_id, params.request_id);
+        let mut entry = PendingEntry {
+            request: params.request,
+            wall_time: params.wall_time,
+            timestamp: params.timestamp,
+            response: None,
+            response_timestamp: None,
+            end_timestamp: None,
+            data_length: 0,
+            encoded_data_length: None,
+            redirect_url: String::new(),
+            error: None,
+            has_extra_headers: false,
+        };
+        if let Some(extra_headers) = self.early_extra_headers.remove(&key) {
+            entry.merge_extra_headers(extra_headers);
+        }
+
+        self.in_flight.insert(key, self.entries.len());
+        self.entries.push(entry);
+    }
+
+    fn request_extra_info(&mut self, target_id: &str, params: RequestWillBeSentExtraInfo) {
+        // Each hop of a redirect gets its own extra info, so an entry that already has
+        // its headers means these belong to the next hop, not sent yet
+        match self.pending(target_id, &params.request_id) {
+            Some(entry) if !entry.has_extra_headers => entry.merge_extra_headers(params.headers),
+            _ => {
+                self.early_extra_headers.insert(
+                    format!("{}:{}", target_id, params.request_id),
+                    params.headers,
+                );
+            }
+        }
+    }
+
+    fn response_received(&mut self, target_id: &str, params: ResponseReceived) {
+        if let Some(entry) = self.pending(target_id, &params.request_id) {
+            entry.response = Some(params.response);
+            entry.response_timestamp = Some(params.timestamp);
+        }
+    }
+
+    fn data_received(&mut self, target_id: &str, params: DataReceived) {
+        if let Some(entry) = self.pending(target_id, &params.request_id) {
+            entry.data_length += params.data_length;
+        }
+    }
+
+    fn loading_finished(&mut self, target_id: &str, params: LoadingFinished) {
+        if let Some(entry) = self.pending(target_id, &params.request_id) {
+            entry.end_timestamp = Some(params.timestamp);
+            entry.encoded_data_length = Some(params.encoded_data_length);
+        }
+        self.finish(target_id, &params.request_id);
+    }
+
+    fn loading_failed(&mut self, target_id: &str, params: LoadingFailed) {
+        if let Some(entry) = self.pending(target_id, &params.request_id) {
+            entry.end_timestamp = Some(params.timestamp);
+            entry.error = Some(params.error_text);
+        }
+        self.finish(target_id, &params.request_id);
+    }
+
+    fn into_har(self) -> Har {
+        Har {
+            log: HarLog {
+                version: "1.2",
+                creator: HarCreator {
+                    name: env!("CARGO_PKG_NAME"),
+                    version: env!("CARGO_PKG_VERSION"),
+                },
+                entries: self.entries.into_iter().map(PendingEntry::into_har).collect(),
+            },
+        }
+    }
+}
+
+impl PendingEntry {
+    // The sent headers win over what the renderer planned to send
+    fn merge_extra_headers(&mut self, extra_headers: HashMap<String, String>) {
+        let headers = &mut self.request.headers;
+        headers.retain(|name, _| !extra_headers.keys().any(|n| n.eq_ignore_ascii_case(name)));
+        headers.extend(extra_headers);
+        self.has_extra_headers = true;
+    }
+
+    fn into_har(self) -> HarEntry {
+        let timings = self.timings();
+        let time = [
+            timings.blocked,
+            timings.dns,
+            timings.connect,
+            timings.send,
+            timings.wait,
+            timings.receive,
+        ]
+        .iter()
+        .filter(|t| **t > 0.0)
+        .sum();
+
+        let http_version = http_version(
+            self.response
+                .as_ref()
+                .and_then(|r| r.protocol.as_deref()),
+        );
+
+        let post_data = self.request.post_data.as_ref().map(|text| HarPostData {
+            mime_type: header_value(&self.request.headers, "content-type").unwrap_or_default(),
+            text: text.clone(),
+        });
+
+        let request = HarRequest {
+            method: self.request.method.clone(),
+            url: self.request.url.clone(),
+            http_version: http_version.clone(),
+            cookies: header_value(&self.request.headers, "cookie")
+                .map(|cookies| cookies.split(';').filter_map(parse_cookie).collect())
+                .unwrap_or_default(),
+            headers: har_headers(&self.request.headers),
+            query_string: query_string(&self.request.url),
+            body_size: post_data.as_ref().map_or(0, |p| p.text.len() as i64),
+            post_data,
+            headers_size: -1,
+        };
+
+        let response = match &self.response {
+            Some(response) => HarResponse {
+                status: response.status,
+                status_text: response.status_text.clone(),
+                http_version,
+                cookies: response
+                    .headers
+                    .iter()
+                    .filter(|(name, _)| name.eq_ignore_ascii_case("set-cookie"))
+                    // Chrome joins repeated Set-Cookie headers with newlines
+                    .flat_map(|(_, value)| value.lines().filter_map(parse_cookie))
+                    .collect(),
+                headers: har_headers(&response.headers),
+                content: HarContent {
+                    size: self.data_length,
+                    mime_type: response.mime_type.clone(),
+                },
+                redirect_url: self.redirect_url.clone(),
+                headers_size: -1,
+                body_size: self.encoded_data_length.map_or(-1, |l| l as i64),
+            },
+            // Failed or unfinished requests never got a response
+            None => HarResponse {
+                status: 0,
+                status_text: String::new(),
+                http_version,
+                cookies: Vec::new(),
+                headers: Vec::new(),
+                content: HarContent {
+                    size: 0,
+                    mime_type: "x-unknown".to_string(),
+                },
+                redirect_url: String::new(),
+                headers_size: -1,
+                body_size: -1,
+            },
+        };
+
+        HarEntry {
+            started_date_time: iso_time(self.wall_time),
+            time,
+            request,
+            response,
+            cache: HarCache {},
+            timings,
+            server_ip_address: self
+                .response
+                .as_ref()
+                .and_then(|r| r.remote_ip_address.clone()),
+            error: self.error,
+        }
+    }
+
+    fn timings(&self) -> HarTimings {
+        let end = self
+            .end_timestamp
+            .or(self.response_timestamp)
+            .unwrap_or(self.timestamp);
+
+        match self.response.as_ref().and_then(|r| r.timing.as_ref()) {
+            Some(timing) => {
+                let phase = |start: f64, end: f64| if start >= 0.0 { end - start } else { -1.0 };
+                // Time queued before the request was started, plus any stall before
+                // the first network phase
+                let queued = ((timing.request_time - self.timestamp) * 1000.0).max(0.0);
+                let stalled = [timing.dns_start, timing.connect_start, timing.send_start]
+                    .into_iter()
+                    .find(|t| *t >= 0.0)
+                    .unwrap_or(0.0);
+
+                HarTimings {
+                    blocked: queued + stalled,
+                    dns: phase(timing.dns_start, timing.dns_end),
+                    connect: phase(timing.connect_start, timing.connect_end),
+                    send: (timing.send_end - timing.send_start).max(0.0),
+                    wait: (timing.receive_headers_end - timing.send_end).max(0.0),
+                    receive: ((end - timing.request_time) * 1000.0 - timing.receive_headers_end)
+                        .max(0.0),
+                    ssl: phase(timing.ssl_start, timing.ssl_end),
+                }
+            }
+            None => {
+                // Cached and failed requests carry no timing, so split on the events we saw
+                let headers_received = self.response_timestamp.unwrap_or(end);
+                HarTimings {
+                    blocked: -1.0,
+                    dns: -1.0,
+                    connect: -1.0,
+                    send: 0.0,
+                    wait: ((headers_received - self.timestamp) * 1000.0).max(0.0),
+                    receive: ((end - headers_received) * 1000.0).max(0.0),
+                    ssl: -1.0,
+                }
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn chrome_start_har_recording(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+) -> Result<String, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    if session.har_recorder.is_recording() {
+        return Err("A HAR recording is already running for this session".into());
+    }
+
+    let (stop, stopped) = oneshot::channel();
+    *session.har_recorder.0.lock().unwrap() = Some(HarRecording::new(stop));
+    // Listen before enabling so the first requests are not missed
+    spawn_collector(&session, stopped);
+
+    if let Err(e) = enable_network(&session).await {
+        session.connections.stop_network_events();
+        session.har_recorder.0.lock().unwrap().take();
+        return Err(e.into());
+    }
+
+    println!("Started HAR recording for session {}", session_id);
+    Ok("HAR recording started".to_string())
+}
+
+#[tauri::command]
+pub async fn chrome_stop_har_recording(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    path: Option<String>,
+) -> Result<HarSummary, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let recording = session
+        .har_recorder
+        .0
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or("No HAR recording is running for this session")?;
+    session.connections.stop_network_events();
+
+    // Keep the domain on if the frontend still listens to Network events
+    if !session.connections.is_forwarded("Network") {
+        session.connections.disable_domain("Network").await;
+    }
+
+    let har = recording.into_har();
+    let entry_count = har.log.entries.len();
+    let contents = serde_json::to_string_pretty(&har)
+        .map_err(|e| format!("Failed to serialize HAR: {}", e))?;
+
+    let path = path.unwrap_or_else(|| default_export_path("har"));
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    println!("Saved HAR with {} entries to {}", entry_count, path);
+    Ok(HarSummary { path, entry_count })
+}
+
+// Turn on Network events for every page; tabs opened later get them when attached
+async fn enable_network(session: &ChromeSession) -> Result<(), String> {
+    let targets = check_chrome_devtools(session.debug_port)
+        .await
+        .map_err(|e| format!("Chrome session is no longer responsive: {}", e))?;
+
+    for target in targets.iter().filter(|t| t.target_type == "page") {
+        let client = session.connections.client(&target.websocket_debugger_url).await?;
+        session
+            .connections
+            .enable_domain(&client, "Network")
+            .await
+            .map_err(|e| format!("Failed to enable network events: {}", e))?;
+    }
+
+    Ok(())
+}
+
+fn spawn_collector(session: &ChromeSession, mut stopped: oneshot::Receiver<()>) {
+    // Network events get a channel of their own, so a busy page can't leave gaps in the HAR
+    let mut events = session.connections.network_events();
+    let recorder = session.har_recorder.clone();
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let event = tokio::select! {
+                _ = &mut stopped => break,
+                event = events.recv() => event,
+            };
+
+            match event {
+                Some(event) => recorder.record(event),
+                None => break,
+            }
+        }
+    });
+}
+
+fn header_value(headers: &HashMap<String, String>, name: &str) -> Option<String> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.clone())
+}
+
+fn har_headers(headers: &HashMap<String, String>) -> Vec<HarNameValue> {
+    let mut headers: Vec<HarNameValue> = headers
+        .iter()
+        .map(|(name, value)| HarNameValue {
+            name: name.clone(),
+            value: value.clone(),
+        })
+        .collect();
+    headers.sort_by(|a, b| a.name.cmp(&b.name));
+    headers
+}
+
+fn query_string(url: &str) -> Vec<HarNameValue> {
+    reqwest::Url::parse(url)
+        .map(|url| {
+            url.query_pairs()
+                .map(|(name, value)| HarNameValue {
+                    name: name.to_string(),
+                    value: value.to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// `name=value` from a Cookie pair or the first part of a Set-Cookie line
+fn parse_cookie(cookie: &str) -> Option<HarNameValue> {
+    let pair = cookie.split(';').next()?.trim();
+    let (name, value) = pair.split_once('=')?;
+    Some(HarNameValue {
+        name: name.trim().to_string(),
+        value: value.trim().to_string(),
+    })
+}
+
+fn http_version(protocol: Option<&str>) -> String {
+    match protocol {
+        Some("h2") => "HTTP/2".to_string(),
+        Some(p) if p.starts_with("h3") => "HTTP/3".to_string(),
+        Some(p) => p.to_uppercase(),
+        None => String::new(),
+    }
+}
+
+fn iso_time(wall_time: f64) -> String {
+    chrono::DateTime::from_timestamp_millis((wall_time * 1000.0) as i64)
+        .unwrap_or(chrono::DateTime::UNIX_EPOCH)
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chromium::protocol::network::ResourceTiming;
+
+    fn request(url: &str, headers: &[(&str, &str)]) -> Request {
+        Request {
+            url: url.to_string(),
+            method: "GET".to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            post_data: None,
+        }
+    }
+
+    fn response(headers: &[(&str, &str)], timing: Option<ResourceTiming>) -> Response {
+        Response {
+            status: 200,
+            status_text: "OK".to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            mime_type: "text/html".to_string(),
+            protocol: Some("h2".to_string()),
+            remote_ip_address: None,
+            encoded_data_length: None,
+            timing,
+        }
+    }
+
+    fn will_be_sent(request_id: &str, request: Request) -> RequestWillBeSent {
+        RequestWillBeSent {
+            request_id: request_id.to_string(),
+            request,
+            timestamp: 10.0,
+            wall_time: 1_700_000_000.0,
+            redirect_response: None,
+        }
+    }
+
+    fn extra_info(request_id: &str, headers: &[(&str, &str)]) -> RequestWillBeSentExtraInfo {
+        RequestWillBeSentExtraInfo {
+            request_id: request_id.to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    fn recording() -> HarRecording {
+        HarRecording::new(oneshot::channel().0)
+    }
+
+    fn cookies(cookies: &[HarNameValue]) -> Vec<(&str, &str)> {
+        cookies
+            .iter()
+            .map(|c| (c.name.as_str(), c.value.as_str()))
+            .collect()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn request_cookies_come_from_the_extra_info_headers() {
+        let mut recording = recording();
+        recording.request_will_be_sent(
+            "tab",
+            will_be_sent("1", request("https://example.com/", &[("Accept", "*/*")])),
+        );
+        recording.request_extra_info(
+            "tab",
+            extra_info("1", &[("accept", "text/html"), ("cookie", "a=1; b=two")]),
+        );
+
+        let har = recording.into_har();
+        let request = &har.log.entries[0].request;
+        assert_eq!(cookies(&request.cookies), [("a", "1"), ("b", "two")]);
+        // The sent value replaces the planned one, whatever its case
+        let accept: Vec<&str> = request
+            .headers
+            .iter()
+            .filter(|h| h.name.eq_ignore_ascii_case("accept"))
+            .map(|h| h.value.as_str())
+            .collect();
+        assert_eq!(accept, ["text/html"]);
+    }
+
+    #[test]
+    fn extra_info_arriving_first_is_kept_for_its_request() {
+        let mut recording = recording();
+        recording.request_extra_info("tab", extra_info("1", &[("Cookie", "session=abc")]));
+        recording.request_will_be_sent("tab", will_be_sent("1", request("https://example.com/", &[])));
+
+        let har = recording.into_har();
+        assert_eq!(
+            cookies(&har.log.entries[0].request.cookies),
+            [("session", "abc")]
+        );
+    }
+
+    #[test]
+    fn extra_info_of_the_next_redirect_hop_waits_for_it() {
+        let mut recording = recording();
+        recording.request_will_be_sent("tab", will_be_sent("1", request("http://example.com/", &[])));
+        recording.request_extra_info("tab", extra_info("1", &[("Cookie", "hop=1")]));
+        // The second hop's headers come before the redirect is reported
+        recording.request_extra_info("tab", extra_info("1", &[("Cookie", "hop=2")]));
+
+        let mut redirect = will_be_sent("1", request("https://example.com/", &[]));
+        redirect.redirect_response = Some(response(&[("Location", "https://example.com/")], None));
+        recording.request_will_be_sent("tab", redirect);
+
+        let har = recording.into_har();
+        assert_eq!(cookies(&har.log.entries[0].request.cookies), [("hop", "1")]);
+        assert_eq!(cookies(&har.log.entries[1].request.cookies), [("hop", "2")]);
+        assert_eq!(har.log.entries[0].response.redirect_url, "https://example.com/");
+    }
+
+    #[test]
+    fn response_cookies_come_from_each_set_cookie_line() {
+        let mut recording = recording();
+        recording.request_will_be_sent("tab", will_be_sent("1", request("https://example.com/", &[])));
+        recording.response_received(
+            "tab",
+            ResponseReceived {
+                request_id: "1".to_string(),
+                loader_id: None,
+                frame_id: None,
+                resource_type: None,
+                timestamp: 10.5,
+                response: response(
+                    &[("set-cookie", "a=1; Path=/; HttpOnly\nb=2; Secure")],
+                    None,
+                ),
+            },
+        );
+
+        let har = recording.into_har();
+        assert_eq!(
+            cookies(&har.log.entries[0].response.cookies),
+            [("a", "1"), ("b", "2")]
+        );
+    }
+
+    #[test]
+    fn parse_cookie_needs_a_name_value_pair() {
+        let cookie = parse_cookie(" id = 42 ; Path=/").unwrap();
+        assert_eq!((cookie.name.as_str(), cookie.value.as_str()), ("id", "42"));
+        assert!(parse_cookie("HttpOnly").is_none());
+    }
+
+    fn entry(response: Option<Response>) -> PendingEntry {
+        PendingEntry {
+            request: request("https://example.com/", &[]),
+            wall_time: 1_700_000_000.0,
+            timestamp: 100.0,
+            response_timestamp: response.as_ref().map(|_| 100.05),
+            response,
+            end_timestamp: Some(100.11),
+            data_length: 0,
+            encoded_data_length: None,
+            redirect_url: String::new(),
+            error: None,
+            has_extra_headers: false,
+        }
+    }
+
+    #[test]
+    fn timings_follow_the_resource_timing() {
+        let timing = ResourceTiming {
+            request_time: 100.01,
+            dns_start: 1.0,
+            dns_end: 5.0,
+            connect_start: 5.0,
+            connect_end: 20.0,
+            ssl_start: 10.0,
+            ssl_end: 20.0,
+            send_start: 21.0,
+            send_end: 22.0,
+            receive_headers_end: 50.0,
+        };
+        let entry = entry(Some(response(&[], Some(timing))));
+
+        let timings = entry.timings();
+        assert_close(timings.blocked, 11.0); // 10ms queued, 1ms stalled
+        assert_close(timings.dns, 4.0);
+        assert_close(timings.connect, 15.0);
+        assert_close(timings.ssl, 10.0);
+        assert_close(timings.send, 1.0);
+        assert_close(timings.wait, 28.0);
+        assert_close(timings.receive, 50.0);
+
+        // ssl is part of connect, so it is left out of the total
+        assert_close(entry.into_har().time, 109.0);
+    }
+
+    #[test]
+    fn timings_of_a_reused_connection_skip_dns_and_connect() {
+        let timing = ResourceTiming {
+            request_time: 100.0,
+            dns_start: -1.0,
+            dns_end: -1.0,
+            connect_start: -1.0,
+            connect_end: -1.0,
+            ssl_start: -1.0,
+            ssl_end: -1.0,
+            send_start: 2.0,
+            send_end: 3.0,
+            receive_headers_end: 40.0,
+        };
+
+        let timings = entry(Some(response(&[], Some(timing)))).timings();
+        assert_close(timings.blocked, 2.0);
+        assert_eq!(timings.dns, -1.0);
+        assert_eq!(timings.connect, -1.0);
+        assert_eq!(timings.ssl, -1.0);
+        assert_close(timings.wait, 37.0);
+        assert_close(timings.receive, 70.0);
+    }
+
+    #[test]
+    fn timings_without_resource_timing_split_on_events() {
+        let timings = entry(Some(response(&[], None))).timings();
+        assert_eq!(timings.blocked, -1.0);
+        assert_eq!(timings.send, 0.0);
+        assert_close(timings.wait, 50.0);
+        assert_close(timings.receive, 60.0);
+
+        // A failed request never got its response
+        let timings = entry(None).timings();
+        assert_close(timings.wait, 110.0);
+        assert_close(timings.receive, 0.0);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::chromium::frames::{frame_origin, PageFrame};
+use crate::chromium::protocol::input::{
+    DispatchKeyEvent, DispatchMouseEvent, InsertText, MODIFIER_ALT, MODIFIER_CTRL, MODIFIER_META,
+    MODIFIER_SHIFT,
+};
+use crate::chromium::protocol::CdpClient;
+use crate::chromium::vimium::ElementAction;
+use crate::chromium::vimium_script::generate_element_locate_script;
+use crate::error::CommandError;
+use serde::Deserialize;
+use serde_json::json;
+
+// Where the hinted element is after scrolling it into view
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ElementLocation {
+    success: bool,
+    message: Option<String>, // Why the element could not be used
+    tag_name: String,
+    // Page coordinates, like PageElement
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    scroll_x: f64,
+    scroll_y: f64,
+}
+
+impl ElementLocation {
+    // Center in the viewport of the element's own frame
+    fn viewport_center(&self) -> (f64, f64) {
+        (
+            self.x - self.scroll_x + self.width / 2.0,
+            self.y - self.scroll_y + self.height / 2.0,
+        )
+    }
+}
+
+// Perform an element action with CDP input events, which pages see as real
+// user input (`isTrusted` is true), unlike the events the action script fakes
+pub async fn perform_trusted_action(
+    client: &CdpClient,
+    frame: &PageFrame,
+    action: &ElementAction,
+) -> Result<serde_json::Value, CommandError> {
+    let (focus, select) = match action.action_type.as_str() {
+        "click" | "right_click" | "hover" => (false, false),
+        "focus" => (true, false),
+        "fill" | "set_value" => (true, true),
+        other => {
+            return Ok(json!({
+                "success": false,
+                "message": format!("Unknown action type: {}", other),
+            }))
+        }
+    };
+
+    let location: ElementLocation = frame
+        .client(client)
+        .evaluate_in(
+            frame.context_id,
+            &generate_element_locate_script(&action.hint, focus, select),
+        )
+        .await?;
+    if !location.success {
+        return Ok(json!({ "success": false, "message": location.message }));
+    }
+
+    let modifiers = modifier_mask(action.modifier_keys.as_deref().unwrap_or_default());
+    // Iframe coordinates are offset by where the frame sits, measured after scrolling
+    let (x, y) = location.viewport_center();
+    let (origin_x, origin_y) = frame_origin(client, frame).await?;
+    let (x, y) = (x + origin_x, y + origin_y);
+
+    match action.action_type.as_str() {
+        "click" => click(client, x, y, "left", modifiers).await?,
+        "right_click" => click(client, x, y, "right", modifiers).await?,
+        "hover" => move_mouse(client, x, y, modifiers).await?,
+        "fill" | "set_value" => {
+            // The content is selected, so the new text replaces it
+            match action.value.as_deref().unwrap_or_default() {
+                "" => press_key(client, "Backspace", 8).await?,
+                value => {
+                    client
+                        .execute(InsertText {
+                            text: value.to_string(),
+                        })
+                        .await?;
+                }
+            }
+        }
+        _ => {} // Focus was given by the locate script
+    }
+
+    Ok(json!({
+        "success": true,
+        "message": format!("{} action performed on {} element", action.action_type, location.tag_name),
+        "input_mode": "trusted",
+    }))
+}
+
+// Mask for the `modifiers` field from "ctrl", "shift", "alt" and "meta"
+fn modifier_mask(keys: &[String]) -> u32 {
+    keys.iter().fold(0, |mask, key| {
+        mask | match key.to_lowercase().as_str() {
+            "alt" | "option" => MODIFIER_ALT,
+            "ctrl" | "control" => MODIFIER_CTRL,
+            "meta" | "cmd" | "command" => MODIFIER_META,
+            "shift" => MODIFIER_SHIFT,
+            _ => 0,
+        }
+    })
+}
+
+async fn move_mouse(client: &CdpClient, x: f64, y: f64, modifiers: u32) -> Result<(), CommandError> {
+    client
+        .execute(DispatchMouseEvent {
+            event_type: "mouseMoved".to_string(),
+            x,
+            y,
+            modifiers,
+            ..Default::default()
+        })
+        .await?;
+    Ok(())
+}
+
+// Move, press and release, the same sequence a real click produces
+async fn click(
+    client: &CdpClient,
+    x: f64,
+    y: f64,
+    button: &str,
+    modifiers: u32,
+) -> Result<(), CommandError> {
+    move_mouse(client, x, y, modifiers).await?;
+
+    let buttons = if button == "right" { 2 } else { 1 };
+    for (event_type, buttons) in [("mousePressed", buttons), ("mouseReleased", 0)] {
+        client
+            .execute(DispatchMouseEvent {
+                event_type: event_type.to_string(),
+                x,
+                y,
+                modifiers,
+                button: Some(button.to_string()),
+                buttons: Some(buttons),
+                click_count: Some(1),
+            })
+            .await?;
+    }
+    Ok(())
+}
+
+// Press and release a key that produces no text, such as Backspace
+async fn press_key(client: &CdpClient, key: &str, key_code: u32) -> Result<(), CommandError> {
+    for event_type in ["rawKeyDown", "keyUp"] {
+        client
+            .execute(DispatchKeyEvent {
+                event_type: event_type.to_string(),
+                key: Some(key.to_string()),
+                code: Some(key.to_string()),
+                windows_virtual_key_code: Some(key_code),
+                ..Default::default()
+            })
+            .await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,383 @@
+use crate::chromium::cdp::{page_websocket_url, CdpConnectionPool, CdpEvent};
+use crate::chromium::lib::{check_chrome_devtools, ChromeSession};
+use crate::chromium::protocol::fetch::{self, HeaderEntry, RequestPaused};
+use crate::chromium::sessions::SessionRegistry;
+use crate::error::CommandError;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::State;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkRule {
+    #[serde(default = "new_rule_id")]
+    pub id: String,
+    pub url_pattern: Option<String>, // Wildcards: `*` any run of characters, `?` one character
+    pub resource_type: Option<String>, // "Document", "Script", "XHR", "Fetch", "Image", ...
+    pub action: RuleAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleAction {
+    Block,
+    Mock {
+        status: Option<u16>,
+        headers: Option<HashMap<String, String>>,
+        body: Option<String>,
+    },
+    RewriteHeaders {
+        set: Option<HashMap<String, String>>,
+        remove: Option<Vec<String>>,
+    },
+}
+
+fn new_rule_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+impl NetworkRule {
+    fn matches(&self, paused: &RequestPaused) -> bool {
+        let url_matches = self
+            .url_pattern
+            .as_deref()
+            .is_none_or(|pattern| wildcard_match(pattern, &paused.request.url));
+        let type_matches = self
+            .resource_type
+            .as_deref()
+            .is_none_or(|resource_type| resource_type.eq_ignore_ascii_case(&paused.resource_type));
+
+        url_matches && type_matches
+    }
+}
+
+// Rules of a session, checked in order; the first match decides
+#[derive(Debug, Clone, Default)]
+pub struct NetworkRules {
+    rules: Arc<Mutex<Vec<NetworkRule>>>,
+    handler_started: Arc<AtomicBool>,
+}
+
+impl NetworkRules {
+    fn list(&self) -> Vec<NetworkRule> {
+        self.rules.lock().unwrap().clone()
+    }
+
+    fn first_match(&self, paused: &RequestPaused) -> Option<NetworkRule> {
+        self.rules
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|rule| rule.matches(paused))
+            .cloned()
+    }
+}
+
+#[tauri::command]
+pub async fn chrome_add_network_rule(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    rule: NetworkRule,
+) -> Result<NetworkRule, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    session.network_rules.rules.lock().unwrap().push(rule.clone());
+    start_interception(&session).await?;
+
+    Ok(rule)
+}
+
+#[tauri::command]
+pub async fn chrome_load_network_rules(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    path: String,
+) -> Result<Vec<NetworkRule>, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read rules file {}: {}", path, e))?;
+    let rules: Vec<NetworkRule> = serde_json::from_str(&contents)
+        .map_err(|e| format!("Failed to parse rules file {}: {}", path, e))?;
+
+    println!("Loaded {} network rules from {}", rules.len(), path);
+    session.network_rules.rules.lock().unwrap().extend(rules);
+    start_interception(&session).await?;
+
+    Ok(session.network_rules.list())
+}
+
+#[tauri::command]
+pub async fn chrome_remove_network_rule(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    rule_id: String,
+) -> Result<Vec<NetworkRule>, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let remaining = {
+        let mut rules = session.network_rules.rules.lock().unwrap();
+        let count = rules.len();
+        rules.retain(|rule| rule.id != rule_id);
+        if rules.len() == count {
+            return Err(format!("Network rule {} not found", rule_id).into());
+        }
+        rules.len()
+    };
+
+    // Without rules there is no reason to keep pausing every request
+    if remaining == 0 {
+        session.connections.disable_domain("Fetch").await;
+    }
+
+    Ok(session.network_rules.list())
+}
+
+#[tauri::command]
+pub fn chrome_list_network_rules(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+) -> Result<Vec<NetworkRule>, CommandError> {
+    let session = sessions.get(&session_id)?;
+    Ok(session.network_rules.list())
+}
+
+// Pause requests on every page of the session and let the rules handle them
+async fn start_interception(session: &ChromeSession) -> Result<(), String> {
+    // The handler has to be listening before requests start pausing
+    if !session.network_rules.handler_started.swap(true, Ordering::SeqCst) {
+        spawn_request_handler(session);
+    }
+
+    let targets = check_chrome_devtools(session.debug_port)
+        .await
+        .map_err(|e| format!("Chrome session is no longer responsive: {}", e))?;
+
+    for target in targets.iter().filter(|t| t.target_type == "page") {
+        let client = session.connections.client(&target.websocket_debugger_url).await?;
+        session
+            .connections
+            .enable_domain(&client, "Fetch")
+            .await
+            .map_err(|e| format!("Failed to enable request interception: {}", e))?;
+    }
+
+    Ok(())
+}
+
+fn spawn_request_handler(session: &ChromeSession) {
+    // Paused requests get a channel of their own, so none is lost to a busy event stream
+    let mut paused_requests = session.connections.paused_requests();
+    let pool = session.connections.downgrade();
+    let rules = session.network_rules.clone();
+    let debug_port = session.debug_port;
+
+    tauri::async_runtime::spawn(async move {
+        while let Some(event) = paused_requests.recv().await {
+            let pool = match pool.upgrade() {
+                Some(pool) => pool,
+                None => break,
+            };
+
+            let rules = rules.clone();
+            tauri::async_runtime::spawn(async move {
+                let target_id = event.target_id.clone();
+                let request_id = event.params["requestId"].as_str().map(str::to_string);
+                if let Err(e) = handle_paused_request(&pool, &rules, debug_port, event).await {
+                    println!("Failed to handle paused request: {}", e);
+                    // A request left paused would hang the page load
+                    if let Some(request_id) = request_id {
+                        release_paused_request(&pool, debug_port, &target_id, request_id).await;
+                    }
+                }
+            });
+        }
+    });
+}
+
+async fn handle_paused_request(
+    pool: &CdpConnectionPool,
+    rules: &NetworkRules,
+    debug_port: u16,
+    event: CdpEvent,
+) -> Result<(), String> {
+    let paused: RequestPaused = serde_json::from_value(event.params)
+        .map_err(|e| format!("Failed to parse paused request: {}", e))?;
+    let client = pool
+        .client(&page_websocket_url(debug_port, &event.target_id))
+        .await?;
+
+    let rule = match rules.first_match(&paused) {
+        Some(rule) => rule,
+        None => {
+            client
+                .execute(fetch::ContinueRequest {
+                    request_id: paused.request_id,
+                    headers: None,
+                })
+                .await?;
+            return Ok(());
+        }
+    };
+
+    println!("Network rule {} matched {}", rule.id, paused.request.url);
+
+    match rule.action {
+        RuleAction::Block => {
+            client
+                .execute(fetch::FailRequest {
+                    request_id: paused.request_id,
+                    error_reason: "BlockedByClient".to_string(),
+                })
+                .await?;
+        }
+        RuleAction::Mock {
+            status,
+            headers,
+            body,
+        } => {
+            let response_headers = headers
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, value)| HeaderEntry { name, value })
+                .collect();
+
+            client
+                .execute(fetch::FulfillRequest {
+                    request_id: paused.request_id,
+                    response_code: status.unwrap_or(200),
+                    response_headers,
+                    body: base64::engine::general_purpose::STANDARD
+                        .encode(body.unwrap_or_default()),
+                })
+                .await?;
+        }
+        RuleAction::RewriteHeaders { set, remove } => {
+            let remove = remove.unwrap_or_default();
+            let set = set.unwrap_or_default();
+
+            let mut headers: Vec<HeaderEntry> = paused
+                .request
+                .headers
+                .into_iter()
+                .filter(|(name, _)| {
+                    !remove.iter().any(|r| r.eq_ignore_ascii_case(name))
+                        && !set.keys().any(|s| s.eq_ignore_ascii_case(name))
+                })
+                .map(|(name, value)| HeaderEntry { name, value })
+                .collect();
+            headers.extend(set.into_iter().map(|(name, value)| HeaderEntry { name, value }));
+
+            client
+                .execute(fetch::ContinueRequest {
+                    request_id: paused.request_id,
+                    headers: Some(headers),
+                })
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+// Let a request through unchanged after its rule failed, or fail it if even that is refused
+async fn release_paused_request(
+    pool: &CdpConnectionPool,
+    debug_port: u16,
+    target_id: &str,
+    request_id: String,
+) {
+    let client = match pool.client(&page_websocket_url(debug_port, target_id)).await {
+        Ok(client) => client,
+        Err(e) => {
+            println!("Failed to release paused request {}: {}", request_id, e);
+            return;
+        }
+    };
+
+    let continued = client
+        .execute(fetch::ContinueRequest {
+            request_id: request_id.clone(),
+            headers: None,
+        })
+        .await;
+    if let Err(e) = continued {
+        println!("Failed to continue paused request {}: {}", request_id, e);
+        let failed = client
+            .execute(fetch::FailRequest {
+                request_id: request_id.clone(),
+                error_reason: "Failed".to_string(),
+            })
+            .await;
+        if let Err(e) = failed {
+            println!("Failed to fail paused request {}: {}", request_id, e);
+        }
+    }
+}
+
+// Glob-style match where `*` matches any run of characters and `?` exactly one
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            // Let the last `*` swallow one more character and retry
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wildcard_match;
+
+    #[test]
+    fn wildcard_match_literal() {
+        assert!(wildcard_match("https://example.com/", "https://example.com/"));
+        assert!(!wildcard_match("https://example.com/", "https://example.com/a"));
+        assert!(!wildcard_match("https://example.com/a", "https://example.com/"));
+    }
+
+    #[test]
+    fn wildcard_match_star() {
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("*", "https://example.com/"));
+        assert!(wildcard_match("*.js", "https://example.com/app.js"));
+        assert!(wildcard_match("*://ads.*/*", "https://ads.example.com/banner.png"));
+        assert!(wildcard_match("https://*/api/*", "https://example.com/api/"));
+        assert!(!wildcard_match("*.js", "https://example.com/app.json"));
+    }
+
+    #[test]
+    fn wildcard_match_backtracks() {
+        // The first `/api/` is not the one that lets the rest match
+        assert!(wildcard_match("*/api/v2", "https://example.com/api/v1/api/v2"));
+        assert!(wildcard_match("*a*b", "aaab"));
+        assert!(!wildcard_match("*a*b", "aaac"));
+    }
+
+    #[test]
+    fn wildcard_match_question_mark() {
+        assert!(wildcard_match("https://example.com/?", "https://example.com/a"));
+        assert!(!wildcard_match("https://example.com/?", "https://example.com/"));
+        assert!(!wildcard_match("https://example.com/?", "https://example.com/ab"));
+    }
+}
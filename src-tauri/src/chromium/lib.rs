@@ -1,23 +1,208 @@
-use crate::{get_chrome_sessions, get_next_message_id};
-use futures_util::{SinkExt, StreamExt};
+use crate::chromium::browsers::browser_candidates;
+use crate::chromium::cdp::{CallOptions, CdpConnectionPool, CdpError};
+use crate::chromium::console::{spawn_console_capture, ConsoleBuffer};
+use crate::chromium::har::HarRecorder;
+use crate::chromium::interception::NetworkRules;
+use crate::chromium::presets::load_preset;
+use crate::chromium::process::{spawn_process_monitor, ChromeProcess};
+use crate::chromium::profiles::{create_ephemeral_profile, remove_profile_dir};
+use crate::chromium::protocol::CdpClient;
+use crate::chromium::sessions::SessionRegistry;
+use crate::chromium::startup::{
+    capture_stderr, reset_active_port_file, wait_for_devtools, StartupLog, DEFAULT_STARTUP_TIMEOUT,
+};
+use crate::chromium::tabs::{resolve_target, spawn_active_target_tracker, ActiveTarget, TabOrder};
+use crate::error::CommandError;
 use serde::{Deserialize, Serialize};
 use std::net::TcpStream;
-use std::process::{Command, Stdio};
+use std::path::PathBuf;
+use std::process::{Child, Command, ExitStatus, Stdio};
 use std::time::Duration;
-use tokio::time::sleep;
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tauri::{AppHandle, State};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ChromeControlOptions {
+    label: Option<String>, // Name shown for the session
     url: Option<String>,
     profile: Option<String>,
+    user_data_dir: Option<String>, // Defaults to the browser's own user-data dir
     debug_port: Option<u16>,
+    auto_relaunch: Option<bool>, // Restart a launched Chrome that crashed, restoring its tabs
+    ephemeral: Option<bool>, // Start in a fresh temporary user-data dir
+    keep_profile: Option<bool>, // Keep the temporary user-data dir when the session closes
+    template_dir: Option<String>, // User-data dir copied into the temporary one
+    preset: Option<String>, // Saved preset these options are applied on top of
+    headless: Option<bool>,
+    window_size: Option<WindowSize>,
+    proxy_server: Option<String>, // e.g. "socks5://127.0.0.1:1080"
+    extensions: Option<Vec<String>>, // Directories of unpacked extensions
+    extra_args: Option<Vec<String>>,
+    browser_path: Option<String>, // Defaults to the first Chrome found on the system
+    startup_timeout_secs: Option<u64>, // How long a launched browser gets to become ready
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WindowSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ChromeControlOptions {
+    // These options, with anything they leave unset taken from `preset`
+    pub fn over_preset(self, preset: ChromeControlOptions) -> Self {
+        Self {
+            label: self.label.or(preset.label),
+            url: self.url.or(preset.url),
+            profile: self.profile.or(preset.profile),
+            user_data_dir: self.user_data_dir.or(preset.user_data_dir),
+            debug_port: self.debug_port.or(preset.debug_port),
+            auto_relaunch: self.auto_relaunch.or(preset.auto_relaunch),
+            ephemeral: self.ephemeral.or(preset.ephemeral),
+            keep_profile: self.keep_profile.or(preset.keep_profile),
+            template_dir: self.template_dir.or(preset.template_dir),
+            preset: self.preset,
+            headless: self.headless.or(preset.headless),
+            window_size: self.window_size.or(preset.window_size),
+            proxy_server: self.proxy_server.or(preset.proxy_server),
+            extensions: self.extensions.or(preset.extensions),
+            extra_args: self.extra_args.or(preset.extra_args),
+            browser_path: self.browser_path.or(preset.browser_path),
+            startup_timeout_secs: self.startup_timeout_secs.or(preset.startup_timeout_secs),
+        }
+    }
+
+    // Presets don't refer to other presets
+    pub fn without_preset(self) -> Self {
+        Self { preset: None, ..self }
+    }
+
+    pub fn startup_timeout(&self) -> Duration {
+        self.startup_timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_STARTUP_TIMEOUT)
+    }
+
+    // Directory of the profile the browser runs in, when its user-data dir is known
+    pub fn profile_path(&self) -> Option<PathBuf> {
+        let user_data_dir = PathBuf::from(self.user_data_dir.as_ref()?);
+        Some(user_data_dir.join(self.profile.as_deref().unwrap_or("Default")))
+    }
+
+    // Same launch, opening `url` instead when one is given
+    pub fn with_start_url(&self, url: Option<String>) -> Self {
+        Self {
+            url: url.or_else(|| self.url.clone()),
+            ..self.clone()
+        }
+    }
+}
+
+// A browser process the app started, until it is handed to its session
+#[derive(Debug)]
+pub struct LaunchedChrome {
+    pub child: Child,
+    pub executable: String,
+    pub startup_log: StartupLog,
+    active_port_file: Option<PathBuf>,
+}
+
+impl LaunchedChrome {
+    pub async fn wait_until_ready(
+        &mut self,
+        debug_port: u16,
+        timeout: Duration,
+    ) -> Result<Vec<ChromeTarget>, CommandError> {
+        wait_for_devtools(
+            &mut self.child,
+            debug_port,
+            self.active_port_file.as_deref(),
+            &self.startup_log,
+            timeout,
+        )
+        .await
+    }
+
+    // Don't leave a browser behind that no session controls
+    pub async fn kill(self) {
+        kill_child(self.child).await;
+    }
+}
+
+// Kill the process and reap it. Waiting blocks, so it runs off the async workers
+pub async fn kill_child(mut child: Child) -> Option<ExitStatus> {
+    if let Err(e) = child.kill() {
+        println!("Failed to kill Chrome process {}: {}", child.id(), e);
+    }
+    tokio::task::spawn_blocking(move || child.wait())
+        .await
+        .ok()?
+        .ok()
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ChromeSession {
     pub(crate) debug_port: u16,
-    session_id: String,
+    pub(crate) session_id: String,
+    #[serde(default)]
+    pub(crate) label: Option<String>, // Name the user gave the session
+    #[serde(default)]
+    pub(crate) profile: Option<String>,
+    #[serde(default)]
+    pub(crate) user_data_dir: Option<String>,
+    #[serde(default)]
+    pub(crate) browser: Option<String>, // e.g. "Chrome/126.0.6478.126", from /json/version
+    #[serde(default)]
+    pub(crate) protocol_version: Option<String>,
+    #[serde(default)]
+    pub(crate) executable: Option<String>, // Binary the session launched, if it launched one
+    #[serde(skip)]
+    pub(crate) connections: CdpConnectionPool,
+    #[serde(skip)]
+    pub(crate) active_target: ActiveTarget,
+    #[serde(skip)]
+    pub(crate) tab_order: TabOrder,
+    #[serde(skip)]
+    pub(crate) network_rules: NetworkRules,
+    #[serde(skip)]
+    pub(crate) har_recorder: HarRecorder,
+    #[serde(skip)]
+    pub(crate) console: ConsoleBuffer,
+    #[serde(default, rename = "launch")]
+    pub(crate) process: ChromeProcess, // Saved as the launch options and temporary profile, if owned
+}
+
+impl ChromeSession {
+    pub(crate) fn new(debug_port: u16, session_id: String) -> Self {
+        Self {
+            debug_port,
+            session_id,
+            label: None,
+            profile: None,
+            user_data_dir: None,
+            browser: None,
+            protocol_version: None,
+            executable: None,
+            connections: CdpConnectionPool::default(),
+            active_target: ActiveTarget::default(),
+            tab_order: TabOrder::default(),
+            network_rules: NetworkRules::default(),
+            har_recorder: HarRecorder::default(),
+            console: ConsoleBuffer::default(),
+            process: ChromeProcess::default(),
+        }
+    }
+
+    // Fill in what the browser reports about itself
+    pub(crate) async fn read_browser_version(&mut self) {
+        match fetch_browser_version(self.debug_port).await {
+            Ok(version) => {
+                println!("Connected to {} (protocol {})", version.browser, version.protocol_version);
+                self.browser = Some(version.browser);
+                self.protocol_version = Some(version.protocol_version);
+            }
+            Err(e) => println!("Failed to read browser version: {}", e),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -29,238 +214,220 @@ pub struct ChromeTarget {
     pub url: String,
     #[serde(rename = "webSocketDebuggerUrl")]
     pub websocket_debugger_url: String,
+    #[serde(default, rename = "parentId", skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>, // Target an iframe is embedded in
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct CDPMessage {
-    id: u32,
-    method: String,
-    params: serde_json::Value,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct CDPResponse {
-    id: u32,
-    result: Option<serde_json::Value>,
-    error: Option<serde_json::Value>,
-}
-
-fn run_async<F, T>(future: F) -> T
-where
-    F: std::future::Future<Output = T>,
-{
-    tokio::runtime::Runtime::new().unwrap().block_on(future)
+// Response of the DevTools `/json/version` endpoint
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BrowserVersionInfo {
+    #[serde(rename = "Browser")]
+    pub browser: String,
+    #[serde(rename = "Protocol-Version")]
+    pub protocol_version: String,
+    #[serde(rename = "User-Agent")]
+    pub user_agent: String,
+    #[serde(rename = "webSocketDebuggerUrl")]
+    pub websocket_debugger_url: String,
 }
 
 #[tauri::command]
-pub fn chrome_debug_info(session_id: String) -> Result<String, String> {
-    crate::run_async(async move {
-        let sessions = get_chrome_sessions();
-        let session = sessions.get(&session_id)
-            .ok_or("Session not found")?.clone();
+pub async fn chrome_debug_info(sessions: State<'_, SessionRegistry>, session_id: String, target_id: Option<String>) -> Result<String, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let mut debug_info = format!("Chrome Debug Info for session {}:\n", session_id);
+    debug_info.push_str(&format!("Debug Port: {}\n", session.debug_port));
+    debug_info.push_str(&format!("Port in use: {}\n", is_port_in_use(session.debug_port)));
+    if let Some(browser) = &session.browser {
+        debug_info.push_str(&format!("Browser: {}\n", browser));
+    }
+    if let Some(protocol_version) = &session.protocol_version {
+        debug_info.push_str(&format!("Protocol version: {}\n", protocol_version));
+    }
+    if let Some(executable) = &session.executable {
+        debug_info.push_str(&format!("Executable: {}\n", executable));
+    }
 
-        let mut debug_info = format!("Chrome Debug Info for session {}:\n", session_id);
-        debug_info.push_str(&format!("Debug Port: {}\n", session.debug_port));
-        debug_info.push_str(&format!("Port in use: {}\n", is_port_in_use(session.debug_port)));
+    match check_chrome_devtools(session.debug_port).await {
+        Ok(targets) => {
+            debug_info.push_str("DevTools API: Responsive\n");
+            debug_info.push_str(&format!("Targets found: {}\n\n", targets.len()));
+
+            for (i, target) in targets.iter().enumerate() {
+                debug_info.push_str(&format!("Target {}:\n", i + 1));
+                debug_info.push_str(&format!("  ID: {}\n", target.id));
+                debug_info.push_str(&format!("  Type: {}\n", target.target_type));
+                debug_info.push_str(&format!("  Title: {}\n", target.title));
+                debug_info.push_str(&format!("  URL: {}\n", target.url));
+                debug_info.push_str(&format!("  WebSocket: {}\n", target.websocket_debugger_url));
+                debug_info.push('\n');
+            }
 
-        match check_chrome_devtools(session.debug_port).await {
-            Ok(targets) => {
-                debug_info.push_str(&format!("DevTools API: Responsive\n"));
-                debug_info.push_str(&format!("Targets found: {}\n\n", targets.len()));
-
-                for (i, target) in targets.iter().enumerate() {
-                    debug_info.push_str(&format!("Target {}:\n", i + 1));
-                    debug_info.push_str(&format!("  ID: {}\n", target.id));
-                    debug_info.push_str(&format!("  Type: {}\n", target.target_type));
-                    debug_info.push_str(&format!("  Title: {}\n", target.title));
-                    debug_info.push_str(&format!("  URL: {}\n", target.url));
-                    debug_info.push_str(&format!("  WebSocket: {}\n", target.websocket_debugger_url));
-                    debug_info.push_str("\n");
-                }
+            // Test connection to the target commands would run on
+            if let Ok(target) = resolve_target(&session, target_id.as_deref()).await {
+                debug_info.push_str(&format!("Testing WebSocket connection to target: {}\n", target.id));
 
-                // Test connection to a target
-                if let Some(target) = targets.iter().find(|t| t.target_type == "page" && !t.url.starts_with("chrome-extension://")) {
-                    debug_info.push_str(&format!("Testing WebSocket connection to target: {}\n", target.id));
-
-                    let test_params = serde_json::json!({
-                        "expression": "navigator.userAgent",
-                        "returnByValue": true
-                    });
-
-                    match send_cdp_message(&target.websocket_debugger_url, "Runtime.evaluate", test_params).await {
-                        Ok(_) => {
-                            debug_info.push_str("✅ WebSocket connection test successful\n");
-                        }
-                        Err(e) => {
-                            debug_info.push_str(&format!("❌ WebSocket connection test failed: {}\n", e));
-                        }
+                let test = async {
+                    cdp_client(&session, &target).await?
+                        .evaluate_read_only::<serde_json::Value>(None, "navigator.userAgent").await
+                };
+
+                match test.await {
+                    Ok(_) => {
+                        debug_info.push_str("✅ WebSocket connection test successful\n");
+                    }
+                    Err(e) => {
+                        debug_info.push_str(&format!("❌ WebSocket connection test failed: {}\n", e));
                     }
                 }
             }
-            Err(e) => {
-                debug_info.push_str(&format!("DevTools API: Error - {}\n", e));
-            }
         }
+        Err(e) => {
+            debug_info.push_str(&format!("DevTools API: Error - {}\n", e));
+        }
+    }
 
-        Ok(debug_info)
-    })
+    Ok(debug_info)
 }
 
 #[tauri::command]
-pub fn chrome_get_targets(session_id: String) -> Result<Vec<ChromeTarget>, String> {
-    crate::run_async(async move {
-        let sessions = get_chrome_sessions();
-        let session = sessions.get(&session_id)
-            .ok_or("Session not found")?.clone();
-
-        match check_chrome_devtools(session.debug_port).await {
-            Ok(targets) => Ok(targets),
-            Err(e) => Err(format!("Failed to get Chrome targets: {}", e)),
-        }
-    })
+pub async fn chrome_get_targets(sessions: State<'_, SessionRegistry>, session_id: String) -> Result<Vec<ChromeTarget>, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    check_chrome_devtools(session.debug_port).await
 }
 
 #[tauri::command]
-pub fn chrome_execute_script(session_id: String, script: String) -> Result<String, String> {
-    crate::run_async(async move {
-        let sessions = get_chrome_sessions();
-        let session = sessions.get(&session_id)
-            .ok_or("Session not found")?.clone();
+pub async fn chrome_execute_script(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    script: String,
+    target_id: Option<String>,
+    call_options: Option<CallOptions>,
+) -> Result<String, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    println!("Executing script on session port {}: {}", session.debug_port, script);
+
+    let target = resolve_target(&session, target_id.as_deref()).await?;
+
+    println!("Executing script on target: {} - {}", target.title, target.url);
 
-        println!("Executing script on session port {}: {}", session.debug_port, script);
+    let client = cdp_client(&session, &target)
+        .await?
+        .with_options(call_options);
 
-        // Get current targets
-        let targets = match check_chrome_devtools(session.debug_port).await {
+    match client.evaluate::<serde_json::Value>(&script).await {
+        Ok(value) => Ok(value.to_string()),
+        Err(e) => Err(CommandError::from(e).context("Script execution failed")),
+    }
+}
+
+#[tauri::command]
+pub async fn open_chrome_with_control(
+    app: AppHandle,
+    sessions: State<'_, SessionRegistry>,
+    options: ChromeControlOptions,
+) -> Result<ChromeSession, CommandError> {
+    let mut options = match options.preset.clone() {
+        Some(name) => options.over_preset(load_preset(&app, &name)?),
+        None => options,
+    };
+    let requested_port = options.debug_port.unwrap_or(9222);
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let ephemeral = options.ephemeral.unwrap_or(false);
+
+    println!("Attempting to open Chrome with control on port {}", requested_port);
+
+    // First, check if Chrome is already running and responsive on the requested port.
+    // A temporary profile always needs a browser of its own.
+    if !ephemeral && is_port_in_use(requested_port) {
+        println!("Port {} is in use, checking if Chrome DevTools is responding...", requested_port);
+
+        match check_chrome_devtools(requested_port).await {
             Ok(targets) => {
-                println!("Chrome DevTools responsive, found {} targets", targets.len());
-                targets
+                println!("Found existing Chrome with {} targets", targets.len());
+
+                // Chrome was started elsewhere, so the session does not own its process
+                let mut session = ChromeSession::new(requested_port, session_id.clone());
+                session.label = options.label.clone();
+                session.read_browser_version().await;
+                spawn_active_target_tracker(&session);
+                spawn_console_capture(&session);
+                sessions.insert(session.clone());
+                return Ok(session);
             }
             Err(e) => {
-                return Err(format!("Chrome session is no longer responsive: {}", e));
+                println!("Port is in use but Chrome DevTools not responding: {}", e);
+                // Port is in use by something else, find different port
             }
-        };
-
-        // Find the best target to execute script on (prefer pages over background pages)
-        let target = targets.iter()
-            .find(|t| t.target_type == "page" && !t.url.starts_with("chrome-extension://"))
-            .or_else(|| targets.iter().find(|t| t.target_type == "page"))
-            .or_else(|| targets.first())
-            .ok_or("No suitable target found for script execution")?;
-
-        println!("Executing script on target: {} - {}", target.title, target.url);
-
-        let params = serde_json::json!({
-            "expression": script,
-            "returnByValue": true
-        });
-
-        match send_cdp_message(&target.websocket_debugger_url, "Runtime.evaluate", params).await {
-            Ok(result) => {
-                // Parse the result
-                if let Some(value) = result.get("value") {
-                    Ok(value.to_string())
-                } else if let Some(result_obj) = result.get("result") {
-                    if let Some(value) = result_obj.get("value") {
-                        Ok(value.to_string())
-                    } else {
-                        Ok(result_obj.to_string())
-                    }
-                } else {
-                    Ok(result.to_string())
-                }
-            }
-            Err(e) => Err(format!("Script execution failed: {}", e)),
         }
-    })
-}
+    }
 
-#[tauri::command]
-pub fn open_chrome_with_control(options: ChromeControlOptions) -> Result<ChromeSession, String> {
-    run_async(async move {
-        let requested_port = options.debug_port.unwrap_or(9222);
-        let session_id = uuid::Uuid::new_v4().to_string();
-
-        println!("Attempting to open Chrome with control on port {}", requested_port);
-
-        // First, check if Chrome is already running and responsive on the requested port
-        if is_port_in_use(requested_port) {
-            println!("Port {} is in use, checking if Chrome DevTools is responding...", requested_port);
-
-            match check_chrome_devtools(requested_port).await {
-                Ok(targets) => {
-                    println!("Found existing Chrome with {} targets", targets.len());
-
-                    let session = ChromeSession {
-                        debug_port: requested_port,
-                        session_id: session_id.clone(),
-                    };
-                    get_chrome_sessions().insert(session_id.clone(), session.clone());
-                    return Ok(session);
-                }
-                Err(e) => {
-                    println!("Port is in use but Chrome DevTools not responding: {}", e);
-                    // Port is in use by something else, find different port
-                }
-            }
-        }
+    // Find an available port for new Chrome instance
+    let available_port = if is_port_in_use(requested_port) {
+        let new_port = find_available_port(requested_port + 1);
+        println!("Port {} in use, using port {} instead", requested_port, new_port);
+        new_port
+    } else {
+        println!("Port {} is available", requested_port);
+        requested_port
+    };
 
-        // Find an available port for new Chrome instance
-        let available_port = if is_port_in_use(requested_port) {
-            let new_port = find_available_port(requested_port + 1);
-            println!("Port {} in use, using port {} instead", requested_port, new_port);
-            new_port
-        } else {
-            println!("Port {} is available", requested_port);
-            requested_port
-        };
-
-        // Launch new Chrome instance
-        println!("Launching new Chrome instance...");
-        match launch_new_chrome(&options, available_port).await {
-            Ok(_) => {
-                println!("Chrome launched, waiting for startup...");
-
-                // Wait longer for Chrome to fully start up
-                for i in 1..=10 {
-                    sleep(Duration::from_secs(1)).await;
-                    println!("Startup check {}/10...", i);
-
-                    if is_port_in_use(available_port) {
-                        // Check if DevTools API is responding
-                        match check_chrome_devtools(available_port).await {
-                            Ok(targets) => {
-                                println!("Chrome DevTools is responsive with {} targets", targets.len());
-
-                                let session = ChromeSession {
-                                    debug_port: available_port,
-                                    session_id: session_id.clone(),
-                                };
-                                get_chrome_sessions().insert(session_id.clone(), session.clone());
-                                return Ok(session);
-                            }
-                            Err(e) => {
-                                println!("DevTools check {}/10 failed: {}", i, e);
-                                if i == 10 {
-                                    return Err(format!("Chrome launched but DevTools API not responding: {}", e));
-                                }
-                            }
-                        }
-                    }
-                }
+    let profile_dir = if ephemeral {
+        let dir = create_ephemeral_profile(&session_id, options.template_dir.as_deref())?;
+        options.user_data_dir = Some(dir.to_string_lossy().to_string());
+        Some(dir)
+    } else {
+        None
+    };
 
-                Err("Chrome startup timeout".to_string())
+    // Launch new Chrome instance
+    println!("Launching new Chrome instance...");
+    let mut launched = match launch_new_chrome(&options, available_port).await {
+        Ok(launched) => launched,
+        Err(e) => {
+            if let Some(dir) = &profile_dir {
+                remove_profile_dir(dir).await;
             }
-            Err(e) => Err(format!("Failed to launch Chrome: {}", e)),
+            return Err(format!("Failed to launch Chrome: {}", e).into());
         }
-    })
+    };
+
+    println!("Chrome launched, waiting for startup...");
+    if let Err(e) = launched
+        .wait_until_ready(available_port, options.startup_timeout())
+        .await
+    {
+        launched.kill().await;
+        if let Some(dir) = &profile_dir {
+            remove_profile_dir(dir).await;
+        }
+        return Err(e);
+    }
+
+    let mut session = ChromeSession::new(available_port, session_id.clone());
+    session.label = options.label.clone();
+    session.profile = options.profile.clone();
+    session.user_data_dir = options.user_data_dir.clone();
+    session.executable = Some(launched.executable.clone());
+    session.read_browser_version().await;
+    let auto_relaunch = options.auto_relaunch.unwrap_or(false);
+    let keep_profile = options.keep_profile.unwrap_or(false);
+    session.process.adopt(launched, options, auto_relaunch);
+    if let Some(dir) = profile_dir {
+        session.process.own_profile_dir(dir, keep_profile);
+    }
+    spawn_active_target_tracker(&session);
+    spawn_console_capture(&session);
+    spawn_process_monitor(app, &session);
+    sessions.insert(session.clone());
+    Ok(session)
 }
 
 // Check if a port is in use
-fn is_port_in_use(port: u16) -> bool {
-    match TcpStream::connect(format!("127.0.0.1:{}", port)) {
-        Ok(_) => true,
-        Err(_) => false,
-    }
+pub fn is_port_in_use(port: u16) -> bool {
+    TcpStream::connect(format!("127.0.0.1:{}", port)).is_ok()
 }
 
 // Find an available port starting from the given port
@@ -274,91 +441,73 @@ fn find_available_port(start_port: u16) -> u16 {
 }
 
 // Check if Chrome DevTools is responding on the given port
-pub async fn check_chrome_devtools(debug_port: u16) -> Result<Vec<ChromeTarget>, String> {
+pub async fn check_chrome_devtools(debug_port: u16) -> Result<Vec<ChromeTarget>, CommandError> {
     let url = format!("http://127.0.0.1:{}/json", debug_port);
+    let unreachable = |reason: String| CommandError::DevToolsUnreachable {
+        port: Some(debug_port),
+        reason,
+    };
 
     match reqwest::get(&url).await {
         Ok(response) => {
             if response.status().is_success() {
                 match response.json::<Vec<ChromeTarget>>().await {
                     Ok(targets) => Ok(targets),
-                    Err(e) => Err(format!("Failed to parse Chrome targets: {}", e)),
+                    Err(e) => Err(CommandError::ParseFailed {
+                        input: url,
+                        message: format!("Failed to parse Chrome targets: {}", e),
+                    }),
                 }
             } else {
-                Err(format!("Chrome DevTools HTTP API returned status: {}", response.status()))
+                Err(unreachable(format!("HTTP API returned status: {}", response.status())))
             }
         }
-        Err(e) => Err(format!("Failed to connect to Chrome DevTools HTTP API: {}", e)),
+        Err(e) => Err(unreachable(format!("Failed to connect to HTTP API: {}", e))),
     }
 }
 
-// Send CDP message and wait for response
-pub async fn send_cdp_message(websocket_url: &str, method: &str, params: serde_json::Value) -> Result<serde_json::Value, String> {
-    let message_id = get_next_message_id();
-
-    let cdp_message = CDPMessage {
-        id: message_id,
-        method: method.to_string(),
-        params,
-    };
-
-    let message_json = serde_json::to_string(&cdp_message)
-        .map_err(|e| format!("Failed to serialize CDP message: {}", e))?;
-
-    println!("Connecting to WebSocket: {}", websocket_url);
-    println!("Sending CDP message: {}", message_json);
+// Fetch browser-level info, including the WebSocket URL of the browser target itself
+pub async fn fetch_browser_version(debug_port: u16) -> Result<BrowserVersionInfo, String> {
+    let url = format!("http://127.0.0.1:{}/json/version", debug_port);
 
-    let (ws_stream, _) = connect_async(websocket_url).await
-        .map_err(|e| format!("WebSocket connection failed: {}", e))?;
+    let response = reqwest::get(&url)
+        .await
+        .map_err(|e| format!("Failed to connect to Chrome DevTools HTTP API: {}", e))?;
 
-    let (mut write, mut read) = ws_stream.split();
-
-    // Send the message
-    write.send(Message::Text(message_json)).await
-        .map_err(|e| format!("Failed to send message: {}", e))?;
-
-    // Wait for response with timeout
-    let response = tokio::time::timeout(Duration::from_secs(10), async {
-        while let Some(msg) = read.next().await {
-            match msg {
-                Ok(Message::Text(text)) => {
-                    println!("Received response: {}", text);
+    if !response.status().is_success() {
+        return Err(format!("Chrome DevTools HTTP API returned status: {}", response.status()));
+    }
 
-                    if let Ok(cdp_response) = serde_json::from_str::<CDPResponse>(&text) {
-                        if cdp_response.id == message_id {
-                            if let Some(error) = cdp_response.error {
-                                return Err(format!("CDP Error: {}", error));
-                            }
-                            return Ok(cdp_response.result.unwrap_or(serde_json::Value::Null));
-                        }
-                    }
-                }
-                Ok(_) => continue,
-                Err(e) => return Err(format!("WebSocket error: {}", e)),
-            }
-        }
-        Err("No response received".to_string())
-    }).await;
+    response
+        .json::<BrowserVersionInfo>()
+        .await
+        .map_err(|e| format!("Failed to parse Chrome version info: {}", e))
+}
 
-    match response {
-        Ok(result) => result,
-        Err(_) => Err("Request timeout".to_string()),
-    }
+// Typed CDP client for the target, over the session's long-lived connection to it
+pub async fn cdp_client(session: &ChromeSession, target: &ChromeTarget) -> Result<CdpClient, CdpError> {
+    session.connections.client(&target.websocket_debugger_url).await
 }
 
-async fn launch_new_chrome(options: &ChromeControlOptions, debug_port: u16) -> Result<(), String> {
+pub async fn launch_new_chrome(
+    options: &ChromeControlOptions,
+    debug_port: u16,
+) -> Result<LaunchedChrome, String> {
     println!("Launching new Chrome instance on port {}", debug_port);
 
     // Build Chrome command with remote debugging
     let mut args = vec![
         format!("--remote-debugging-port={}", debug_port),
-        "--disable-web-security".to_string(),
         "--disable-features=VizDisplayCompositor".to_string(),
         "--no-first-run".to_string(),
         "--disable-default-apps".to_string(),
         "--no-default-browser-check".to_string(),
     ];
 
+    if let Some(user_data_dir) = &options.user_data_dir {
+        args.push(format!("--user-data-dir={}", user_data_dir));
+    }
+
     // Add profile if specified
     if let Some(profile) = &options.profile {
         if profile != "Default" {
@@ -366,6 +515,27 @@ async fn launch_new_chrome(options: &ChromeControlOptions, debug_port: u16) -> R
         }
     }
 
+    if options.headless.unwrap_or(false) {
+        args.push("--headless=new".to_string());
+    }
+
+    if let Some(size) = &options.window_size {
+        args.push(format!("--window-size={},{}", size.width, size.height));
+    }
+
+    if let Some(proxy_server) = &options.proxy_server {
+        args.push(format!("--proxy-server={}", proxy_server));
+    }
+
+    if let Some(extensions) = options.extensions.as_ref().filter(|e| !e.is_empty()) {
+        args.push(format!("--load-extension={}", extensions.join(",")));
+    }
+
+    // Anything else is passed through as given, e.g. "--disable-web-security"
+    if let Some(extra_args) = &options.extra_args {
+        args.extend(extra_args.iter().cloned());
+    }
+
     // Add URL if specified, otherwise start with blank page
     if let Some(url) = &options.url {
         args.push(url.clone());
@@ -375,56 +545,41 @@ async fn launch_new_chrome(options: &ChromeControlOptions, debug_port: u16) -> R
 
     println!("Chrome launch args: {:?}", args);
 
-    let result = {
-        #[cfg(target_os = "windows")]
-        {
-            Command::new("cmd")
-                .args(["/C", "start", "", "chrome"])
-                .args(&args)
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .spawn()
-        }
+    let active_port_file = reset_active_port_file(options.user_data_dir.as_deref());
 
-        #[cfg(target_os = "macos")]
-        {
-            Command::new("open")
-                .args(["-a", "Google Chrome", "--args"])
-                .args(&args)
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .spawn()
-        }
+    // Chrome is started directly, not through a launcher, so the session owns its process
+    let mut last_error = String::new();
+    let executables = match &options.browser_path {
+        Some(path) => vec![path.clone()],
+        None => browser_candidates()
+            .into_iter()
+            .map(|(_, path)| path.to_string_lossy().to_string())
+            .collect(),
+    };
+    if executables.is_empty() {
+        return Err("No Chromium-based browser found on this system".to_string());
+    }
 
-        #[cfg(target_os = "linux")]
+    for executable in executables {
+        match Command::new(&executable)
+            .args(&args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
         {
-            Command::new("google-chrome")
-                .args(&args)
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .spawn()
-                .or_else(|_| {
-                    Command::new("chromium-browser")
-                        .args(&args)
-                        .stdout(Stdio::null())
-                        .stderr(Stdio::null())
-                        .spawn()
-                })
-                .or_else(|_| {
-                    Command::new("chromium")
-                        .args(&args)
-                        .stdout(Stdio::null())
-                        .stderr(Stdio::null())
-                        .spawn()
-                })
+            Ok(mut child) => {
+                println!("Chrome process {} spawned from {}", child.id(), executable);
+                let startup_log = capture_stderr(&mut child);
+                return Ok(LaunchedChrome {
+                    child,
+                    executable,
+                    startup_log,
+                    active_port_file,
+                });
+            }
+            Err(e) => last_error = format!("{}: {}", executable, e),
         }
-    };
-
-    match result {
-        Ok(_) => {
-            println!("Chrome process spawned successfully");
-            Ok(())
-        },
-        Err(e) => Err(format!("Failed to spawn Chrome process: {}", e)),
     }
-}
\ No newline at end of file
+
+    Err(format!("Failed to spawn Chrome process: {}", last_error))
+}
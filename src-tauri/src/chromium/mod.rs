@@ -1,3 +1,21 @@
+pub mod browsers;
+pub mod capture;
+pub mod cdp;
+pub mod console;
+pub mod events;
+pub mod frames;
+pub mod har;
+pub mod input;
+pub mod interception;
 pub mod lib;
+pub mod navigation;
+pub mod presets;
+pub mod process;
+pub mod profiles;
+pub mod protocol;
+pub mod sessions;
+pub mod startup;
+pub mod storage;
+pub mod tabs;
 pub mod vimium;
 pub mod vimium_script;
\ No newline at end of file
@@ -0,0 +1,461 @@
+use crate::chromium::cdp::{CallOptions, CdpEvent};
+use crate::chromium::lib::{cdp_client, ChromeSession, ChromeTarget};
+use crate::chromium::protocol::{self, network, page, CdpClient};
+use crate::chromium::sessions::SessionRegistry;
+use crate::chromium::tabs::resolve_target;
+use crate::error::CommandError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::State;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+// Pages can take much longer to load than a single CDP call takes to answer
+const DEFAULT_NAVIGATION_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NavigationResult {
+    pub url: String, // Where the tab ended up, after redirects
+    pub status: Option<i64>, // HTTP status of the document; None for same-document and cached loads
+    pub error: Option<String>, // Why the navigation failed, e.g. "net::ERR_NAME_NOT_RESOLVED"
+}
+
+enum Navigation {
+    Url(String),
+    HistoryEntry(i64),
+    Reload(bool), // Whether to bypass the cache
+}
+
+#[tauri::command]
+pub async fn chrome_navigate(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    url: String,
+    wait_until: Option<String>, // "dom_content_loaded", "load" (default) or "network_idle"
+    target_id: Option<String>,
+    call_options: Option<CallOptions>,
+) -> Result<NavigationResult, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    reqwest::Url::parse(&url).map_err(|e| CommandError::ParseFailed {
+        input: url.clone(),
+        message: format!("Invalid URL {}: {}", url, e),
+    })?;
+
+    let target = resolve_target(&session, target_id.as_deref()).await?;
+    navigate(
+        &session,
+        &target,
+        Navigation::Url(url),
+        wait_until.as_deref(),
+        call_options,
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn chrome_go_back(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    wait_until: Option<String>,
+    target_id: Option<String>,
+    call_options: Option<CallOptions>,
+) -> Result<NavigationResult, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let target = resolve_target(&session, target_id.as_deref()).await?;
+    let entry_id = history_entry(&session, &target, -1).await?;
+    navigate(
+        &session,
+        &target,
+        Navigation::HistoryEntry(entry_id),
+        wait_until.as_deref(),
+        call_options,
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn chrome_go_forward(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    wait_until: Option<String>,
+    target_id: Option<String>,
+    call_options: Option<CallOptions>,
+) -> Result<NavigationResult, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let target = resolve_target(&session, target_id.as_deref()).await?;
+    let entry_id = history_entry(&session, &target, 1).await?;
+    navigate(
+        &session,
+        &target,
+        Navigation::HistoryEntry(entry_id),
+        wait_until.as_deref(),
+        call_options,
+    )
+    .await
+}
+
+// Like chrome_reload_tab, but waits for the page to load again
+#[tauri::command]
+pub async fn chrome_reload(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    ignore_cache: Option<bool>,
+    wait_until: Option<String>,
+    target_id: Option<String>,
+    call_options: Option<CallOptions>,
+) -> Result<NavigationResult, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let target = resolve_target(&session, target_id.as_deref()).await?;
+    navigate(
+        &session,
+        &target,
+        Navigation::Reload(ignore_cache.unwrap_or(false)),
+        wait_until.as_deref(),
+        call_options,
+    )
+    .await
+}
+
+// Id of the history entry `offset` steps away from the current one
+async fn history_entry(
+    session: &ChromeSession,
+    target: &ChromeTarget,
+    offset: isize,
+) -> Result<i64, CommandError> {
+    let client = cdp_client(session, target).await?;
+    let history = client.execute(page::GetNavigationHistory {}).await?;
+
+    history
+        .current_index
+        .checked_add_signed(offset)
+        .and_then(|index| history.entries.get(index))
+        .map(|entry| entry.id)
+        .ok_or_else(|| {
+            let direction = if offset < 0 { "back" } else { "forward" };
+            format!("No page to go {} to in this tab's history", direction).into()
+        })
+}
+
+async fn navigate(
+    session: &ChromeSession,
+    target: &ChromeTarget,
+    navigation: Navigation,
+    wait_until: Option<&str>,
+    call_options: Option<CallOptions>,
+) -> Result<NavigationResult, CommandError> {
+    let lifecycle_event = match wait_until.unwrap_or("load") {
+        "dom_content_loaded" => "DOMContentLoaded",
+        "load" => "load",
+        "network_idle" => "networkIdle",
+        other => return Err(format!("Unknown load state: {}", other).into()),
+    };
+    let timeout = call_options
+        .as_ref()
+        .and_then(|options| options.timeout_ms)
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_NAVIGATION_TIMEOUT);
+
+    let client = cdp_client(session, target)
+        .await?
+        .with_options(call_options);
+
+    // Subscribed before navigating so no event of the new document is missed
+    let mut events = session.connections.subscribe_events();
+    session.connections.start_navigation(&target.id);
+    let result = wait_for_navigation(
+        &client,
+        &mut events,
+        target,
+        navigation,
+        lifecycle_event,
+        timeout,
+    )
+    .await;
+    if session.connections.finish_navigation(&target.id) {
+        disable_load_events(session, &client).await;
+    }
+
+    if let Ok(navigation) = &result {
+        println!(
+            "Navigated {} to {} ({:?})",
+            target.id, navigation.url, navigation.status
+        );
+    }
+    result
+}
+
+async fn wait_for_navigation(
+    client: &CdpClient,
+    events: &mut broadcast::Receiver<CdpEvent>,
+    target: &ChromeTarget,
+    navigation: Navigation,
+    lifecycle_event: &str,
+    timeout: Duration,
+) -> Result<NavigationResult, CommandError> {
+    enable_load_events(client).await?;
+
+    tokio::select! {
+        result = tokio::time::timeout(
+            timeout,
+            start_and_wait(client, events, &target.id, navigation, lifecycle_event),
+        ) => result.unwrap_or_else(|_| {
+            Err(CommandError::Timeout {
+                operation: format!("waiting for {} on {}", lifecycle_event, target.url),
+            })
+        }),
+        _ = client.cancelled() => Err(CommandError::Cancelled {
+            operation: format!("navigation of {}", target.url),
+        }),
+    }
+}
+
+// Turn on the events a navigation is followed by. Enabling twice is harmless,
+// so this does not depend on what other navigations of the target did
+async fn enable_load_events(client: &CdpClient) -> Result<(), CommandError> {
+    for domain in ["Page", "Network"] {
+        protocol::enable_domain(client, domain).await?;
+    }
+    client
+        .execute(page::SetLifecycleEventsEnabled { enabled: true })
+        .await?;
+    Ok(())
+}
+
+// Leave the target as it was once its last navigation is done. Checked now rather
+// than when navigating, as the session may have enabled a domain in the meantime
+async fn disable_load_events(session: &ChromeSession, client: &CdpClient) {
+    if let Err(e) = client
+        .execute(page::SetLifecycleEventsEnabled { enabled: false })
+        .await
+    {
+        println!("Failed to disable lifecycle events after navigating: {}", e);
+    }
+
+    let session_domains = session.connections.enabled_domains();
+    for domain in ["Page", "Network"] {
+        if session_domains.iter().any(|d| d == domain) {
+            continue;
+        }
+        if let Err(e) = protocol::disable_domain(client, domain).await {
+            println!("Failed to disable {} after navigating: {}", domain, e);
+        }
+    }
+}
+
+async fn start_and_wait(
+    client: &CdpClient,
+    events: &mut broadcast::Receiver<CdpEvent>,
+    target_id: &str,
+    navigation: Navigation,
+    lifecycle_event: &str,
+) -> Result<NavigationResult, CommandError> {
+    // The loader of the document being replaced tells a missed commit apart from none
+    let frame = client
+        .execute(page::GetFrameTree {})
+        .await?
+        .frame_tree
+        .frame;
+    let frame_id = frame.id;
+    let previous_loader = frame.loader_id;
+
+    // Known up front only for Page.navigate, otherwise taken from the commit
+    let mut loader_id = None;
+    match navigation {
+        Navigation::Url(url) => {
+            let response = client.execute(page::Navigate { url }).await?;
+            if let Some(error) = response.error_text {
+                return Ok(NavigationResult {
+                    url: current_url(client).await?,
+                    status: None,
+                    error: Some(error),
+                });
+            }
+            match response.loader_id {
+                Some(id) => loader_id = Some(id),
+                // Same-document navigations are done as soon as they return
+                None => {
+                    return Ok(NavigationResult {
+                        url: current_url(client).await?,
+                        status: None,
+                        error: None,
+                    })
+                }
+            }
+        }
+        Navigation::HistoryEntry(entry_id) => {
+            client
+                .execute(page::NavigateToHistoryEntry { entry_id })
+                .await?;
+        }
+        Navigation::Reload(ignore_cache) => {
+            client.execute(page::Reload { ignore_cache }).await?;
+        }
+    }
+
+    let status = wait_for_lifecycle(
+        client,
+        events,
+        target_id,
+        &frame_id,
+        &previous_loader,
+        loader_id,
+        lifecycle_event,
+    )
+    .await?;
+    Ok(NavigationResult {
+        url: current_url(client).await?,
+        status,
+        error: None,
+    })
+}
+
+// Follow the main frame until its new document fires `lifecycle_event`.
+// Returns the document's HTTP status, when its response was seen.
+async fn wait_for_lifecycle(
+    client: &CdpClient,
+    events: &mut broadcast::Receiver<CdpEvent>,
+    target_id: &str,
+    frame_id: &str,
+    previous_loader: &str,
+    mut loader_id: Option<String>,
+    lifecycle_event: &str,
+) -> Result<Option<i64>, CommandError> {
+    // Document responses by loader id, they arrive before the loader is known
+    let mut statuses: HashMap<String, i64> = HashMap::new();
+
+    loop {
+        let event = match events.recv().await {
+            Ok(event) if event.target_id == target_id => event,
+            Ok(_) => continue,
+            Err(RecvError::Lagged(skipped)) => {
+                // The skipped events may have been the ones waited for, so ask the page
+                println!(
+                    "Navigation wait skipped {} events, checking the page",
+                    skipped
+                );
+                if reached_lifecycle(client, previous_loader, &mut loader_id, lifecycle_event)
+                    .await?
+                {
+                    return Ok(loader_id.and_then(|loader| statuses.get(&loader).copied()));
+                }
+                continue;
+            }
+            Err(RecvError::Closed) => return Err("Chrome session closed while navigating".into()),
+        };
+
+        match event.method.as_str() {
+            "Network.responseReceived" => {
+                let Ok(params) = serde_json::from_value::<network::ResponseReceived>(event.params) else {
+                    continue;
+                };
+                if params.resource_type.as_deref() == Some("Document")
+                    && params.frame_id.as_deref() == Some(frame_id)
+                {
+                    if let Some(loader) = params.loader_id {
+                        statuses.insert(loader, params.response.status);
+                    }
+                }
+            }
+            "Page.frameNavigated" if loader_id.is_none() => {
+                let Ok(params) = serde_json::from_value::<page::FrameNavigated>(event.params) else {
+                    continue;
+                };
+                if params.frame.id == frame_id {
+                    loader_id = Some(params.frame.loader_id);
+                }
+            }
+            // History entries within the same document fire no lifecycle events
+            "Page.navigatedWithinDocument" if loader_id.is_none() => {
+                let Ok(params) = serde_json::from_value::<page::NavigatedWithinDocument>(event.params) else {
+                    continue;
+                };
+                if params.frame_id == frame_id {
+                    return Ok(None);
+                }
+            }
+            "Page.lifecycleEvent" => {
+                let Ok(params) = serde_json::from_value::<page::LifecycleEvent>(event.params) else {
+                    continue;
+                };
+                if params.frame_id == frame_id
+                    && loader_id.as_deref() == Some(params.loader_id.as_str())
+                    && params.name == lifecycle_event
+                {
+                    return Ok(statuses.get(&params.loader_id).copied());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Whether the new document is already past `lifecycle_event`, judged by its readyState.
+// Adopts the document's loader when its commit was among the missed events
+async fn reached_lifecycle(
+    client: &CdpClient,
+    previous_loader: &str,
+    loader_id: &mut Option<String>,
+    lifecycle_event: &str,
+) -> Result<bool, CommandError> {
+    let current_loader = client
+        .execute(page::GetFrameTree {})
+        .await?
+        .frame_tree
+        .frame
+        .loader_id;
+    let committed = match loader_id.as_deref() {
+        Some(expected) => current_loader == expected,
+        None => current_loader != previous_loader,
+    };
+    if !committed {
+        return Ok(false);
+    }
+    *loader_id = Some(current_loader);
+
+    let ready_state: String = client
+        .evaluate_read_only(None, "document.readyState")
+        .await?;
+    Ok(lifecycle_reached(lifecycle_event, &ready_state))
+}
+
+// readyState has no network idle state; a complete document is the closest to it
+fn lifecycle_reached(lifecycle_event: &str, ready_state: &str) -> bool {
+    match lifecycle_event {
+        "DOMContentLoaded" => matches!(ready_state, "interactive" | "complete"),
+        _ => ready_state == "complete",
+    }
+}
+
+async fn current_url(client: &CdpClient) -> Result<String, CommandError> {
+    let history = client.execute(page::GetNavigationHistory {}).await?;
+    history
+        .entries
+        .get(history.current_index)
+        .map(|entry| entry.url.clone())
+        .ok_or_else(|| "Tab has no navigation history".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lifecycle_reached;
+
+    #[test]
+    fn dom_content_loaded_is_reached_once_interactive() {
+        assert!(!lifecycle_reached("DOMContentLoaded", "loading"));
+        assert!(lifecycle_reached("DOMContentLoaded", "interactive"));
+        assert!(lifecycle_reached("DOMContentLoaded", "complete"));
+    }
+
+    #[test]
+    fn load_and_network_idle_need_a_complete_document() {
+        for lifecycle_event in ["load", "networkIdle"] {
+            assert!(!lifecycle_reached(lifecycle_event, "loading"));
+            assert!(!lifecycle_reached(lifecycle_event, "interactive"));
+            assert!(lifecycle_reached(lifecycle_event, "complete"));
+        }
+    }
+}
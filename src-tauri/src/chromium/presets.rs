@@ -0,0 +1,93 @@
+use crate::chromium::lib::ChromeControlOptions;
+use crate::error::CommandError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+// File in the app's config dir holding the saved presets, keyed by name
+const PRESETS_FILE: &str = "launch_presets.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LaunchPreset {
+    pub name: String,
+    pub options: ChromeControlOptions,
+}
+
+#[tauri::command]
+pub fn chrome_list_launch_presets(app: AppHandle) -> Result<Vec<LaunchPreset>, CommandError> {
+    Ok(read_presets(&app)?
+        .into_iter()
+        .map(|(name, options)| LaunchPreset { name, options })
+        .collect())
+}
+
+#[tauri::command]
+pub fn chrome_save_launch_preset(
+    app: AppHandle,
+    name: String,
+    options: ChromeControlOptions,
+) -> Result<LaunchPreset, CommandError> {
+    if name.trim().is_empty() {
+        return Err("Preset name cannot be empty".into());
+    }
+
+    let mut presets = read_presets(&app)?;
+    presets.insert(name.clone(), options.without_preset());
+    write_presets(&app, &presets)?;
+
+    println!("Saved launch preset {}", name);
+    Ok(LaunchPreset {
+        options: presets[&name].clone(),
+        name,
+    })
+}
+
+#[tauri::command]
+pub fn chrome_delete_launch_preset(app: AppHandle, name: String) -> Result<(), CommandError> {
+    let mut presets = read_presets(&app)?;
+    if presets.remove(&name).is_none() {
+        return Err(format!("Launch preset {} not found", name).into());
+    }
+    write_presets(&app, &presets)
+}
+
+pub fn load_preset(app: &AppHandle, name: &str) -> Result<ChromeControlOptions, CommandError> {
+    read_presets(app)?
+        .remove(name)
+        .ok_or_else(|| format!("Launch preset {} not found", name).into())
+}
+
+fn presets_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(PRESETS_FILE))
+        .map_err(|e| format!("Failed to locate app config dir: {}", e))
+}
+
+fn read_presets(app: &AppHandle) -> Result<BTreeMap<String, ChromeControlOptions>, CommandError> {
+    let path = presets_path(app)?;
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&contents).map_err(|e| CommandError::ParseFailed {
+        input: path.to_string_lossy().to_string(),
+        message: format!("Failed to parse {}: {}", path.display(), e),
+    })
+}
+
+fn write_presets(app: &AppHandle, presets: &BTreeMap<String, ChromeControlOptions>) -> Result<(), CommandError> {
+    let path = presets_path(app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+
+    let contents = serde_json::to_string_pretty(presets)
+        .map_err(|e| format!("Failed to serialize launch presets: {}", e))?;
+    std::fs::write(&path, contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e).into())
+}
@@ -0,0 +1,443 @@
+use crate::chromium::lib::{
+    fetch_browser_version, is_port_in_use, kill_child, launch_new_chrome, ChromeControlOptions,
+    ChromeSession, LaunchedChrome,
+};
+use crate::chromium::profiles::remove_profile_dir;
+use crate::chromium::protocol::browser;
+use crate::chromium::sessions::SessionRegistry;
+use crate::chromium::startup::StartupLog;
+use crate::chromium::tabs::{list_tabs, open_tab, watch_targets};
+use crate::error::CommandError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ExitStatus};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::time::sleep;
+
+// Name of the Tauri event emitted when a Chrome started by the app exits
+pub const CHROME_PROCESS_EVENT: &str = "chrome-process-exited";
+
+const PROCESS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+// Open tabs are remembered every this many polls, for restoring after a crash
+const TAB_SNAPSHOT_POLLS: u32 = 5;
+// Give up relaunching a browser that keeps crashing
+const MAX_RELAUNCHES: u32 = 3;
+// How long Chrome gets to shut down on `Browser.close` before it is killed
+const CLOSE_GRACE_POLLS: u32 = 10;
+const CLOSE_GRACE_INTERVAL: Duration = Duration::from_millis(500);
+
+// The Chrome process a session launched; empty when the session attached to
+// a browser that was already running. Saved with the session as its launch, so the
+// next run of the app can take the browser over again
+#[derive(Debug, Clone, Default)]
+pub struct ChromeProcess(Arc<Mutex<ProcessState>>);
+
+#[derive(Debug, Default)]
+struct ProcessState {
+    child: Option<Child>,
+    startup_log: Option<StartupLog>,
+    options: Option<ChromeControlOptions>,
+    auto_relaunch: bool,
+    profile_dir: Option<PathBuf>, // Temporary user-data dir removed on close
+    keep_profile: bool,
+    closing: bool,
+    open_tabs: Vec<String>,
+    last_exit: Option<ProcessExit>,
+    relaunch_count: u32,
+}
+
+// The part of the process state that outlives the app
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedLaunch {
+    options: ChromeControlOptions,
+    auto_relaunch: bool,
+    profile_dir: Option<PathBuf>,
+    keep_profile: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessExit {
+    pub code: Option<i32>, // None when the process was killed by a signal
+    pub success: bool,
+    pub exited_at: u64, // Seconds since the epoch
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProcessStatus {
+    pub session_id: String,
+    pub owned: bool, // Whether the app launched this Chrome and controls its lifetime
+    pub pid: Option<u32>,
+    pub running: bool,
+    pub auto_relaunch: bool,
+    pub profile_dir: Option<String>,
+    pub relaunch_count: u32,
+    pub last_exit: Option<ProcessExit>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProcessEventPayload {
+    pub session_id: String,
+    pub exit: ProcessExit,
+    pub relaunching: bool,
+}
+
+impl ProcessExit {
+    fn from_status(status: ExitStatus) -> Self {
+        Self {
+            code: status.code(),
+            success: status.success(),
+            exited_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+
+    // Exit of a browser started by an earlier run of the app, whose status is unknown
+    fn reattached(options: &ChromeControlOptions) -> Self {
+        Self {
+            code: None,
+            success: options.profile_path().is_none_or(|path| exited_cleanly(&path)),
+            exited_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+// Chrome notes in the profile's Preferences whether it last shut down cleanly.
+// When that can't be read the exit counts as clean, so it is not relaunched
+fn exited_cleanly(profile_path: &Path) -> bool {
+    std::fs::read_to_string(profile_path.join("Preferences"))
+        .ok()
+        .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+        .and_then(|prefs| prefs["profile"]["exit_type"].as_str().map(str::to_string))
+        .is_none_or(|exit_type| exit_type != "Crashed")
+}
+
+impl Serialize for ChromeProcess {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let state = self.0.lock().unwrap();
+        let launch = state.options.clone().map(|options| SavedLaunch {
+            options,
+            auto_relaunch: state.auto_relaunch,
+            profile_dir: state.profile_dir.clone(),
+            keep_profile: state.keep_profile,
+        });
+        launch.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ChromeProcess {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let process = ChromeProcess::default();
+        if let Some(launch) = Option::<SavedLaunch>::deserialize(deserializer)? {
+            let mut state = process.0.lock().unwrap();
+            state.options = Some(launch.options);
+            state.auto_relaunch = launch.auto_relaunch;
+            state.profile_dir = launch.profile_dir;
+            state.keep_profile = launch.keep_profile;
+        }
+        Ok(process)
+    }
+}
+
+impl ChromeProcess {
+    pub fn adopt(&self, launched: LaunchedChrome, options: ChromeControlOptions, auto_relaunch: bool) {
+        let mut state = self.0.lock().unwrap();
+        state.child = Some(launched.child);
+        state.startup_log = Some(launched.startup_log);
+        state.options = Some(options);
+        state.auto_relaunch = auto_relaunch;
+    }
+
+    pub fn own_profile_dir(&self, dir: PathBuf, keep: bool) {
+        let mut state = self.0.lock().unwrap();
+        state.profile_dir = Some(dir);
+        state.keep_profile = keep;
+    }
+
+    pub fn startup_log(&self) -> Option<StartupLog> {
+        self.0.lock().unwrap().startup_log.clone()
+    }
+
+    pub fn is_owned(&self) -> bool {
+        self.0.lock().unwrap().options.is_some()
+    }
+
+    // Exit status once the process has exited; None while it runs
+    fn poll_exit(&self, debug_port: u16) -> Option<ProcessExit> {
+        let mut guard = self.0.lock().unwrap();
+        let state = &mut *guard;
+        let exit = match (state.child.as_mut(), &state.options) {
+            (Some(child), _) => ProcessExit::from_status(child.try_wait().ok()??),
+            // Launched by an earlier run, so there is no handle on it; it is gone
+            // once its debug port is free
+            (None, Some(options)) if state.last_exit.is_none() => {
+                if is_port_in_use(debug_port) {
+                    return None;
+                }
+                ProcessExit::reattached(options)
+            }
+            (None, _) => return None,
+        };
+
+        state.child = None;
+        state.last_exit = Some(exit.clone());
+        Some(exit)
+    }
+
+    // Returns false when there was no process handle to kill
+    pub async fn kill(&self) -> bool {
+        let child = {
+            let mut state = self.0.lock().unwrap();
+            state.closing = true;
+            state.child.take()
+        };
+
+        let Some(child) = child else {
+            return false;
+        };
+        if let Some(status) = kill_child(child).await {
+            self.0.lock().unwrap().last_exit = Some(ProcessExit::from_status(status));
+        }
+        true
+    }
+
+    // Remove the temporary user-data dir, unless the session asked to keep it
+    pub async fn remove_profile(&self) {
+        let dir = {
+            let state = self.0.lock().unwrap();
+            state.profile_dir.clone().filter(|_| !state.keep_profile)
+        };
+        if let Some(dir) = dir {
+            remove_profile_dir(&dir).await;
+        }
+    }
+
+    fn status(&self, session_id: &str) -> ProcessStatus {
+        let mut state = self.0.lock().unwrap();
+        let pid = state.child.as_ref().map(|child| child.id());
+        let running = match state.child.as_mut() {
+            Some(child) => matches!(child.try_wait(), Ok(None)),
+            // Reattached after a restart: running until the monitor sees it exit
+            None => state.options.is_some() && state.last_exit.is_none(),
+        };
+
+        ProcessStatus {
+            session_id: session_id.to_string(),
+            owned: state.options.is_some(),
+            pid,
+            running,
+            auto_relaunch: state.auto_relaunch,
+            profile_dir: state
+                .profile_dir
+                .as_ref()
+                .map(|dir| dir.to_string_lossy().to_string()),
+            relaunch_count: state.relaunch_count,
+            last_exit: state.last_exit.clone(),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn chrome_session_status(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+) -> Result<ProcessStatus, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    Ok(session.process.status(&session_id))
+}
+
+#[tauri::command]
+pub async fn chrome_close_session(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+) -> Result<ProcessStatus, CommandError> {
+    let session = sessions.remove(&session_id)?;
+
+    // A browser the session only attached to is left running
+    if !session.process.is_owned() {
+        println!("Detached from Chrome session {}", session_id);
+        return Ok(session.process.status(&session_id));
+    }
+
+    session.process.0.lock().unwrap().closing = true;
+
+    // Ask Chrome to shut down cleanly so the profile is saved, then make sure
+    if let Err(e) = close_browser(&session).await {
+        println!("Browser.close failed for session {}: {}", session_id, e);
+    }
+    let mut exited = false;
+    for _ in 0..CLOSE_GRACE_POLLS {
+        if session.process.poll_exit(session.debug_port).is_some() {
+            exited = true;
+            break;
+        }
+        sleep(CLOSE_GRACE_INTERVAL).await;
+    }
+    if !session.process.kill().await && !exited {
+        // Reattached after a restart, with no process handle to kill. Its profile
+        // is still in use, so it is left in place
+        println!("Chrome of session {} did not close, leaving it running", session_id);
+        return Ok(session.process.status(&session_id));
+    }
+    session.process.remove_profile().await;
+
+    println!("Closed Chrome session {}", session_id);
+    Ok(session.process.status(&session_id))
+}
+
+// Kill every Chrome the app launched; called when the app exits if the settings
+// ask for it. Sessions that only attached stay saved, to be reattached on the next start
+pub async fn shutdown_chrome_sessions(sessions: &SessionRegistry) {
+    for session in sessions.list() {
+        if !session.process.is_owned() {
+            continue;
+        }
+        println!("Shutting down Chrome of session {}", session.session_id);
+        // Browsers reattached from an earlier run have no process handle here,
+        // so they keep running and stay saved
+        if session.process.kill().await {
+            session.process.remove_profile().await;
+            let _ = sessions.remove(&session.session_id);
+        }
+    }
+}
+
+async fn close_browser(session: &ChromeSession) -> Result<(), String> {
+    let browser = fetch_browser_version(session.debug_port).await?;
+    let client = session
+        .connections
+        .client(&browser.websocket_debugger_url)
+        .await?;
+    client.execute(browser::Close {}).await?;
+    Ok(())
+}
+
+// Watch the launched process, report when it exits and relaunch it after a
+// crash if the session asked for that
+pub fn spawn_process_monitor(app: AppHandle, session: &ChromeSession) {
+    let session = session.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let mut polls: u32 = 0;
+
+        loop {
+            sleep(PROCESS_POLL_INTERVAL).await;
+            polls += 1;
+
+            if session.process.0.lock().unwrap().closing {
+                break;
+            }
+
+            let exit = match session.process.poll_exit(session.debug_port) {
+                Some(exit) => exit,
+                None => {
+                    if polls.is_multiple_of(TAB_SNAPSHOT_POLLS) {
+                        if let Ok(tabs) = list_tabs(&session).await {
+                            session.process.0.lock().unwrap().open_tabs =
+                                tabs.into_iter().map(|tab| tab.url).collect();
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            // A clean exit means the user closed the browser, only crashes are relaunched
+            let (closing, relaunching) = {
+                let state = session.process.0.lock().unwrap();
+                let relaunching = state.auto_relaunch
+                    && !state.closing
+                    && !exit.success
+                    && state.relaunch_count < MAX_RELAUNCHES;
+                (state.closing, relaunching)
+            };
+
+            println!(
+                "Chrome of session {} exited with {:?} (success: {})",
+                session.session_id, exit.code, exit.success
+            );
+
+            let payload = ProcessEventPayload {
+                session_id: session.session_id.clone(),
+                exit,
+                relaunching,
+            };
+            if let Err(e) = app.emit(CHROME_PROCESS_EVENT, payload) {
+                println!("Failed to emit Chrome exit: {}", e);
+            }
+
+            if !relaunching {
+                // Closing the session cleans up after it itself
+                if !closing {
+                    end_session(&app, &session).await;
+                }
+                break;
+            }
+
+            if let Err(e) = relaunch(&session).await {
+                println!("Failed to relaunch Chrome of session {}: {}", session.session_id, e);
+                end_session(&app, &session).await;
+                break;
+            }
+        }
+    });
+}
+
+// The browser is gone for good, so is the session and its temporary profile
+async fn end_session(app: &AppHandle, session: &ChromeSession) {
+    if app
+        .state::<SessionRegistry>()
+        .remove(&session.session_id)
+        .is_ok()
+    {
+        println!("Removed Chrome session {}, its browser exited", session.session_id);
+    }
+    session.process.remove_profile().await;
+}
+
+// Start Chrome again on the session's port and reopen the tabs it had
+async fn relaunch(session: &ChromeSession) -> Result<(), String> {
+    let (options, open_tabs) = {
+        let mut state = session.process.0.lock().unwrap();
+        state.relaunch_count += 1;
+        (state.options.clone(), state.open_tabs.clone())
+    };
+    let options = options.ok_or("Session did not launch its browser")?;
+
+    println!(
+        "Relaunching Chrome of session {} with {} tabs",
+        session.session_id,
+        open_tabs.len()
+    );
+
+    let options = options.with_start_url(open_tabs.first().cloned());
+    let mut launched = launch_new_chrome(&options, session.debug_port).await?;
+    if let Err(e) = launched
+        .wait_until_ready(session.debug_port, options.startup_timeout())
+        .await
+    {
+        launched.kill().await;
+        return Err(e.into());
+    }
+
+    {
+        let mut state = session.process.0.lock().unwrap();
+        state.child = Some(launched.child);
+        state.startup_log = Some(launched.startup_log);
+    }
+
+    for url in open_tabs.iter().skip(1) {
+        if let Err(e) = open_tab(session, url).await {
+            println!("Failed to restore tab {}: {}", url, e);
+        }
+    }
+
+    // Stale connections are dropped by the pool; new ones pick up the session's domains
+    watch_targets(&session.connections, session.debug_port).await
+}
@@ -0,0 +1,232 @@
+use crate::error::CommandError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// Picture Chrome downloads for profiles signed in to a Google account
+const GAIA_PICTURE_FILE: &str = "Google Profile Picture.png";
+
+// Files marking a user-data dir as in use by a running browser
+const PROFILE_LOCK_FILES: [&str; 4] = ["SingletonLock", "SingletonSocket", "SingletonCookie", "lockfile"];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChromeProfile {
+    pub browser: String,
+    pub user_data_dir: String,
+    pub directory: String, // "Default", "Profile 1", ... as passed to --profile-directory
+    pub name: String,
+    pub avatar: Option<String>, // Path of the profile picture, else Chrome's avatar icon id
+    pub last_used: Option<u64>, // Seconds since the epoch
+}
+
+// The parts of a user-data dir's `Local State` that describe its profiles
+#[derive(Debug, Deserialize)]
+struct LocalState {
+    profile: Option<ProfileSection>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProfileSection {
+    #[serde(default)]
+    info_cache: HashMap<String, ProfileInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProfileInfo {
+    name: Option<String>,
+    avatar_icon: Option<String>,
+    gaia_picture_file_name: Option<String>,
+    active_time: Option<f64>, // Seconds since the epoch
+}
+
+#[tauri::command]
+pub fn chrome_get_profiles() -> Result<Vec<ChromeProfile>, CommandError> {
+    let mut profiles = Vec::new();
+
+    for (browser, user_data_dir) in user_data_dirs() {
+        match read_profiles(browser, &user_data_dir) {
+            Ok(found) => profiles.extend(found),
+            Err(e) => println!("Skipping {} profiles in {}: {}", browser, user_data_dir.display(), e),
+        }
+    }
+
+    Ok(profiles)
+}
+
+// Profiles of one user-data dir, most recently used first
+fn read_profiles(browser: &str, user_data_dir: &Path) -> Result<Vec<ChromeProfile>, String> {
+    let local_state_path = user_data_dir.join("Local State");
+    let contents = std::fs::read_to_string(&local_state_path)
+        .map_err(|e| format!("Failed to read {}: {}", local_state_path.display(), e))?;
+    let local_state: LocalState = serde_json::from_str(&contents)
+        .map_err(|e| format!("Failed to parse {}: {}", local_state_path.display(), e))?;
+
+    let info_cache = local_state
+        .profile
+        .map(|section| section.info_cache)
+        .unwrap_or_default();
+
+    let mut profiles: Vec<ChromeProfile> = info_cache
+        .into_iter()
+        .map(|(directory, info)| {
+            let picture = info
+                .gaia_picture_file_name
+                .filter(|file| !file.is_empty())
+                .unwrap_or_else(|| GAIA_PICTURE_FILE.to_string());
+            let picture_path = user_data_dir.join(&directory).join(picture);
+
+            ChromeProfile {
+                browser: browser.to_string(),
+                user_data_dir: user_data_dir.to_string_lossy().to_string(),
+                name: info.name.unwrap_or_else(|| directory.clone()),
+                avatar: if picture_path.is_file() {
+                    Some(picture_path.to_string_lossy().to_string())
+                } else {
+                    info.avatar_icon
+                },
+                last_used: info.active_time.map(|t| t as u64),
+                directory,
+            }
+        })
+        .collect();
+
+    profiles.sort_by_key(|profile| std::cmp::Reverse(profile.last_used));
+    Ok(profiles)
+}
+
+// User-data dirs of the Chromium-based browsers installed for this user
+fn user_data_dirs() -> Vec<(&'static str, PathBuf)> {
+    let mut candidates: Vec<(&'static str, PathBuf)> = Vec::new();
+
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(local) = dirs::data_local_dir() {
+            for (browser, dir) in [
+                ("Google Chrome", r"Google\Chrome\User Data"),
+                ("Google Chrome Beta", r"Google\Chrome Beta\User Data"),
+                ("Google Chrome Canary", r"Google\Chrome SxS\User Data"),
+                ("Chromium", r"Chromium\User Data"),
+                ("Microsoft Edge", r"Microsoft\Edge\User Data"),
+                ("Brave", r"BraveSoftware\Brave-Browser\User Data"),
+                ("Vivaldi", r"Vivaldi\User Data"),
+            ] {
+                candidates.push((browser, local.join(dir)));
+            }
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(support) = dirs::config_dir() {
+            for (browser, dir) in [
+                ("Google Chrome", "Google/Chrome"),
+                ("Google Chrome Beta", "Google/Chrome Beta"),
+                ("Google Chrome Canary", "Google/Chrome Canary"),
+                ("Chromium", "Chromium"),
+                ("Microsoft Edge", "Microsoft Edge"),
+                ("Brave", "BraveSoftware/Brave-Browser"),
+                ("Vivaldi", "Vivaldi"),
+            ] {
+                candidates.push((browser, support.join(dir)));
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(config) = dirs::config_dir() {
+            for (browser, dir) in [
+                ("Google Chrome", "google-chrome"),
+                ("Google Chrome Beta", "google-chrome-beta"),
+                ("Google Chrome Unstable", "google-chrome-unstable"),
+                ("Chromium", "chromium"),
+                ("Microsoft Edge", "microsoft-edge"),
+                ("Brave", "BraveSoftware/Brave-Browser"),
+                ("Vivaldi", "vivaldi"),
+            ] {
+                candidates.push((browser, config.join(dir)));
+            }
+        }
+
+        // Sandboxed packages keep their profiles under their own home
+        if let Some(home) = dirs::home_dir() {
+            for (browser, dir) in [
+                ("Chromium (snap)", "snap/chromium/common/chromium"),
+                ("Google Chrome (flatpak)", ".var/app/com.google.Chrome/config/google-chrome"),
+                ("Chromium (flatpak)", ".var/app/org.chromium.Chromium/config/chromium"),
+                ("Brave (flatpak)", ".var/app/com.brave.Browser/config/BraveSoftware/Brave-Browser"),
+                ("Microsoft Edge (flatpak)", ".var/app/com.microsoft.Edge/config/microsoft-edge"),
+            ] {
+                candidates.push((browser, home.join(dir)));
+            }
+        }
+    }
+
+    candidates
+        .into_iter()
+        .filter(|(_, dir)| dir.join("Local State").is_file())
+        .collect()
+}
+
+// Fresh user-data dir for one session, optionally seeded from a template dir
+pub fn create_ephemeral_profile(session_id: &str, template_dir: Option<&str>) -> Result<PathBuf, String> {
+    let dir = std::env::temp_dir().join(format!("nebulea-profile-{}", session_id));
+
+    match template_dir {
+        Some(template) => {
+            let template = Path::new(template);
+            if !template.is_dir() {
+                return Err(format!("Profile template {} is not a directory", template.display()));
+            }
+            copy_profile_dir(template, &dir)
+                .map_err(|e| format!("Failed to copy profile template {}: {}", template.display(), e))?;
+        }
+        None => std::fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create profile dir {}: {}", dir.display(), e))?,
+    }
+
+    println!("Created temporary profile {}", dir.display());
+    Ok(dir)
+}
+
+// Copy a user-data dir, leaving out the locks of a browser that may still be using it
+fn copy_profile_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if PROFILE_LOCK_FILES.iter().any(|lock| name == *lock) {
+            continue;
+        }
+
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_profile_dir(&entry.path(), &to.join(&name))?;
+        } else if file_type.is_file() {
+            std::fs::copy(entry.path(), to.join(&name))?;
+        }
+    }
+
+    Ok(())
+}
+
+// Chrome's child processes can hold files for a moment after the browser exits
+pub async fn remove_profile_dir(dir: &Path) {
+    for attempt in 1..=5 {
+        match tokio::fs::remove_dir_all(dir).await {
+            Ok(()) => {
+                println!("Removed temporary profile {}", dir.display());
+                return;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+            Err(e) => {
+                if attempt == 5 {
+                    println!("Failed to remove temporary profile {}: {}", dir.display(), e);
+                    return;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use crate::chromium::protocol::{CdpCommand, Empty};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct Close {}
+
+impl CdpCommand for Close {
+    const METHOD: &'static str = "Browser.close";
+    type Response = Empty;
+}
@@ -0,0 +1,52 @@
+use crate::chromium::protocol::{CdpCommand, Empty};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+pub struct Enable {}
+
+impl CdpCommand for Enable {
+    const METHOD: &'static str = "DOM.enable";
+    const IDEMPOTENT: bool = true;
+    type Response = Empty;
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFrameOwner {
+    pub frame_id: String,
+}
+
+impl CdpCommand for GetFrameOwner {
+    const METHOD: &'static str = "DOM.getFrameOwner";
+    const IDEMPOTENT: bool = true;
+    type Response = GetFrameOwnerResponse;
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFrameOwnerResponse {
+    pub backend_node_id: i64, // The <iframe> element holding the frame
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetBoxModel {
+    pub backend_node_id: i64,
+}
+
+impl CdpCommand for GetBoxModel {
+    const METHOD: &'static str = "DOM.getBoxModel";
+    const IDEMPOTENT: bool = true;
+    type Response = GetBoxModelResponse;
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetBoxModelResponse {
+    pub model: BoxModel,
+}
+
+// Quads are x1, y1, ... x4, y4 clockwise from the top left, in viewport coordinates
+#[derive(Debug, Deserialize)]
+pub struct BoxModel {
+    pub content: Vec<f64>,
+}
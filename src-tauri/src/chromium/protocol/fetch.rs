@@ -0,0 +1,80 @@
+use crate::chromium::protocol::{CdpCommand, Empty};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestPattern {
+    pub url_pattern: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Enable {
+    pub patterns: Vec<RequestPattern>,
+}
+
+impl CdpCommand for Enable {
+    const METHOD: &'static str = "Fetch.enable";
+    const IDEMPOTENT: bool = true;
+    type Response = Empty;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeaderEntry {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContinueRequest {
+    pub request_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<Vec<HeaderEntry>>,
+}
+
+impl CdpCommand for ContinueRequest {
+    const METHOD: &'static str = "Fetch.continueRequest";
+    type Response = Empty;
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailRequest {
+    pub request_id: String,
+    pub error_reason: String,
+}
+
+impl CdpCommand for FailRequest {
+    const METHOD: &'static str = "Fetch.failRequest";
+    type Response = Empty;
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FulfillRequest {
+    pub request_id: String,
+    pub response_code: u16,
+    pub response_headers: Vec<HeaderEntry>,
+    // Base64-encoded response body
+    pub body: String,
+}
+
+impl CdpCommand for FulfillRequest {
+    const METHOD: &'static str = "Fetch.fulfillRequest";
+    type Response = Empty;
+}
+
+// Params of the `Fetch.requestPaused` event
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestPaused {
+    pub request_id: String,
+    pub request: PausedRequest,
+    pub resource_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PausedRequest {
+    pub url: String,
+    pub headers: std::collections::HashMap<String, String>,
+}
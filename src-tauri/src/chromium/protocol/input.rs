@@ -0,0 +1,62 @@
+use crate::chromium::protocol::{CdpCommand, Empty};
+use serde::Serialize;
+
+// Bits of the `modifiers` field
+pub const MODIFIER_ALT: u32 = 1;
+pub const MODIFIER_CTRL: u32 = 2;
+pub const MODIFIER_META: u32 = 4;
+pub const MODIFIER_SHIFT: u32 = 8;
+
+// Coordinates are CSS pixels relative to the viewport
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DispatchMouseEvent {
+    #[serde(rename = "type")]
+    pub event_type: String, // "mousePressed", "mouseReleased", "mouseMoved"
+    pub x: f64,
+    pub y: f64,
+    pub modifiers: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub button: Option<String>, // "none", "left", "middle", "right"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buttons: Option<u32>, // Buttons held down: 1 left, 2 right, 4 middle
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub click_count: Option<u32>,
+}
+
+impl CdpCommand for DispatchMouseEvent {
+    const METHOD: &'static str = "Input.dispatchMouseEvent";
+    type Response = Empty;
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DispatchKeyEvent {
+    #[serde(rename = "type")]
+    pub event_type: String, // "keyDown", "keyUp", "rawKeyDown", "char"
+    pub modifiers: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>, // DOM key value, e.g. "Backspace"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>, // DOM code value, e.g. "Backspace"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub windows_virtual_key_code: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl CdpCommand for DispatchKeyEvent {
+    const METHOD: &'static str = "Input.dispatchKeyEvent";
+    type Response = Empty;
+}
+
+// Types text into the focused element as if it came from an IME
+#[derive(Debug, Serialize)]
+pub struct InsertText {
+    pub text: String,
+}
+
+impl CdpCommand for InsertText {
+    const METHOD: &'static str = "Input.insertText";
+    type Response = Empty;
+}
@@ -0,0 +1,18 @@
+use serde::Deserialize;
+
+// Event params of `Log.entryAdded`
+#[derive(Debug, Deserialize)]
+pub struct EntryAdded {
+    pub entry: LogEntry,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub source: String, // "network", "violation", "intervention", ...
+    pub level: String,  // "verbose", "info", "warning" or "error"
+    pub text: String,
+    pub timestamp: f64, // Milliseconds since the epoch
+    pub url: Option<String>,
+    pub line_number: Option<i64>,
+}
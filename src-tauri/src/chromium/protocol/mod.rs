@@ -0,0 +1,206 @@
+pub mod browser;
+pub mod dom;
+pub mod fetch;
+pub mod input;
+pub mod log;
+pub mod network;
+pub mod page;
+pub mod runtime;
+pub mod target;
+
+use crate::chromium::cdp::{CallOptions, CallPolicy, CdpConnection, CdpError};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::futures::Notified;
+
+// A typed CDP method: its params are the struct itself, its result is `Response`
+pub trait CdpCommand: Serialize {
+    const METHOD: &'static str;
+    // Whether sending it twice does no harm, so a timed-out attempt can be retried.
+    // Off for anything that acts on the page, such as input, navigation or scripts
+    const IDEMPOTENT: bool = false;
+    type Response: DeserializeOwned;
+}
+
+// Result of methods that return nothing
+#[derive(Debug, Deserialize)]
+pub struct Empty {}
+
+// Typed client for one target, backed by the session's connection to it
+#[derive(Clone)]
+pub struct CdpClient {
+    connection: Arc<CdpConnection>,
+    options: CallOptions, // Overrides of the session's call policy
+}
+
+impl CdpClient {
+    pub fn new(connection: Arc<CdpConnection>) -> Self {
+        Self {
+            connection,
+            options: CallOptions::default(),
+        }
+    }
+
+    // Same target, with this call's timeout and retries on top of the session defaults
+    pub fn with_options(mut self, options: Option<CallOptions>) -> Self {
+        if let Some(options) = options {
+            self.options = options;
+        }
+        self
+    }
+
+    pub fn options(&self) -> &CallOptions {
+        &self.options
+    }
+
+    // The session defaults with this client's overrides applied
+    pub fn policy(&self) -> CallPolicy {
+        self.connection.policy().with_options(&self.options)
+    }
+
+    // Resolves when the session's in-flight operations are cancelled
+    pub fn cancelled(&self) -> Notified<'_> {
+        self.connection.cancelled()
+    }
+
+    pub async fn execute<C: CdpCommand>(&self, command: C) -> Result<C::Response, CdpError> {
+        self.execute_as(command, C::IDEMPOTENT).await
+    }
+
+    async fn execute_as<C: CdpCommand>(
+        &self,
+        command: C,
+        idempotent: bool,
+    ) -> Result<C::Response, CdpError> {
+        let params = serde_json::to_value(&command).map_err(|e| {
+            CdpError::Serialization(format!("Failed to serialize {} params: {}", C::METHOD, e))
+        })?;
+
+        let result = self.send(C::METHOD, params, idempotent).await?;
+
+        serde_json::from_value(result).map_err(|e| {
+            CdpError::Serialization(format!("Failed to parse {} result: {}", C::METHOD, e))
+        })
+    }
+
+    // Untyped escape hatch for methods without a struct. Never retried
+    pub async fn call(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, CdpError> {
+        self.send(method, params, false).await
+    }
+
+    async fn send(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+        idempotent: bool,
+    ) -> Result<serde_json::Value, CdpError> {
+        let policy = self.policy();
+
+        let mut attempt = 0;
+        loop {
+            match self
+                .connection
+                .send(method, params.clone(), policy.timeout)
+                .await
+            {
+                Err(e) if policy.should_retry(&e, idempotent, attempt) => {
+                    attempt += 1;
+                    println!(
+                        "Retrying {} ({}/{}) after: {}",
+                        method, attempt, policy.retries, e
+                    );
+                    tokio::select! {
+                        _ = tokio::time::sleep(policy.retry_delay) => {}
+                        _ = self.connection.cancelled() => {
+                            return Err(CdpError::Cancelled(method.to_string()));
+                        }
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+
+    // Evaluate an expression in the page and deserialize the value it returns
+    pub async fn evaluate<T: DeserializeOwned>(&self, expression: &str) -> Result<T, CdpError> {
+        self.evaluate_in(None, expression).await
+    }
+
+    // Same, in the given execution context, e.g. one of an iframe
+    pub async fn evaluate_in<T: DeserializeOwned>(
+        &self,
+        context_id: Option<i64>,
+        expression: &str,
+    ) -> Result<T, CdpError> {
+        let mut command = runtime::Evaluate::returning_value(expression);
+        command.context_id = context_id;
+        self.execute(command).await?.into_value()
+    }
+
+    // Evaluate a script that only reads the page, so it is retried like other idempotent calls
+    pub async fn evaluate_read_only<T: DeserializeOwned>(
+        &self,
+        context_id: Option<i64>,
+        expression: &str,
+    ) -> Result<T, CdpError> {
+        let mut command = runtime::Evaluate::returning_value(expression);
+        command.context_id = context_id;
+        self.execute_as(command, true).await?.into_value()
+    }
+
+    // Evaluate a script that leaves the page the same however often it runs, such as
+    // one that removes what an earlier run added first, so it can be retried as well
+    pub async fn evaluate_idempotent<T: DeserializeOwned>(
+        &self,
+        context_id: Option<i64>,
+        expression: &str,
+    ) -> Result<T, CdpError> {
+        let mut command = runtime::Evaluate::returning_value(expression);
+        command.context_id = context_id;
+        self.execute_as(command, true).await?.into_value()
+    }
+
+    pub fn frame_context(&self, frame_id: &str) -> Option<i64> {
+        self.connection.frame_context(frame_id)
+    }
+}
+
+// Make Chrome start reporting the events of a domain on this target
+pub async fn enable_domain(client: &CdpClient, domain: &str) -> Result<(), CdpError> {
+    match domain {
+        "Page" => client.execute(page::Enable {}).await?,
+        "Runtime" => client.execute(runtime::Enable {}).await?,
+        "Network" => client.execute(network::Enable {}).await?,
+        "DOM" => client.execute(dom::Enable {}).await?,
+        "Target" => client.execute(target::SetDiscoverTargets { discover: true }).await?,
+        // Pause every request, rules decide what happens to it
+        "Fetch" => {
+            client
+                .execute(fetch::Enable {
+                    patterns: vec![fetch::RequestPattern {
+                        url_pattern: "*".to_string(),
+                    }],
+                })
+                .await?
+        }
+        _ => {
+            client
+                .call(&format!("{}.enable", domain), serde_json::json!({}))
+                .await?;
+            return Ok(());
+        }
+    };
+    Ok(())
+}
+
+pub async fn disable_domain(client: &CdpClient, domain: &str) -> Result<(), CdpError> {
+    client
+        .call(&format!("{}.disable", domain), serde_json::json!({}))
+        .await?;
+    Ok(())
+}
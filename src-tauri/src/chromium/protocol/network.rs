@@ -0,0 +1,181 @@
+use crate::chromium::protocol::{CdpCommand, Empty};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize)]
+pub struct Enable {}
+
+impl CdpCommand for Enable {
+    const METHOD: &'static str = "Network.enable";
+    const IDEMPOTENT: bool = true;
+    type Response = Empty;
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Request {
+    pub url: String,
+    pub method: String,
+    pub headers: HashMap<String, String>,
+    pub post_data: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Response {
+    pub status: i64,
+    pub status_text: String,
+    pub headers: HashMap<String, String>,
+    pub mime_type: String,
+    pub protocol: Option<String>,
+    #[serde(rename = "remoteIPAddress")]
+    pub remote_ip_address: Option<String>,
+    pub encoded_data_length: Option<f64>,
+    pub timing: Option<ResourceTiming>,
+}
+
+// Milliseconds relative to `request_time`, -1 when a phase did not happen
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceTiming {
+    pub request_time: f64, // Seconds, same clock as event timestamps
+    pub dns_start: f64,
+    pub dns_end: f64,
+    pub connect_start: f64,
+    pub connect_end: f64,
+    pub ssl_start: f64,
+    pub ssl_end: f64,
+    pub send_start: f64,
+    pub send_end: f64,
+    pub receive_headers_end: f64,
+}
+
+// Event params; `timestamp` is monotonic seconds, `wall_time` seconds since the epoch
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestWillBeSent {
+    pub request_id: String,
+    pub request: Request,
+    pub timestamp: f64,
+    pub wall_time: f64,
+    pub redirect_response: Option<Response>,
+}
+
+// The headers actually sent, including the Cookie header that `request` lacks.
+// May arrive before or after the matching RequestWillBeSent
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestWillBeSentExtraInfo {
+    pub request_id: String,
+    pub headers: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseReceived {
+    pub request_id: String,
+    pub loader_id: Option<String>,
+    pub frame_id: Option<String>,
+    #[serde(rename = "type")]
+    pub resource_type: Option<String>, // "Document", "Script", "XHR", ...
+    pub timestamp: f64,
+    pub response: Response,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataReceived {
+    pub request_id: String,
+    pub data_length: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadingFinished {
+    pub request_id: String,
+    pub timestamp: f64,
+    pub encoded_data_length: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadingFailed {
+    pub request_id: String,
+    pub timestamp: f64,
+    pub error_text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+    pub expires: f64, // Seconds since the epoch, -1 for session cookies
+    pub http_only: bool,
+    pub secure: bool,
+    pub session: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub same_site: Option<String>, // "Strict", "Lax" or "None"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>, // "Low", "Medium" or "High"
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CookieParam {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+    pub secure: bool,
+    pub http_only: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub same_site: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<String>,
+}
+
+impl From<Cookie> for CookieParam {
+    fn from(cookie: Cookie) -> Self {
+        Self {
+            name: cookie.name,
+            value: cookie.value,
+            domain: cookie.domain,
+            path: cookie.path,
+            secure: cookie.secure,
+            http_only: cookie.http_only,
+            same_site: cookie.same_site,
+            // Leaving out the expiry keeps session cookies as session cookies
+            expires: (!cookie.session).then_some(cookie.expires),
+            priority: cookie.priority,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetAllCookies {}
+
+impl CdpCommand for GetAllCookies {
+    const METHOD: &'static str = "Network.getAllCookies";
+    const IDEMPOTENT: bool = true;
+    type Response = GetAllCookiesResponse;
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetAllCookiesResponse {
+    pub cookies: Vec<Cookie>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SetCookies {
+    pub cookies: Vec<CookieParam>,
+}
+
+impl CdpCommand for SetCookies {
+    const METHOD: &'static str = "Network.setCookies";
+    type Response = Empty;
+}
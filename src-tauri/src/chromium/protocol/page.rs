@@ -0,0 +1,245 @@
+use crate::chromium::protocol::{CdpCommand, Empty};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+pub struct Enable {}
+
+impl CdpCommand for Enable {
+    const METHOD: &'static str = "Page.enable";
+    const IDEMPOTENT: bool = true;
+    type Response = Empty;
+}
+
+#[derive(Debug, Serialize)]
+pub struct AddScriptToEvaluateOnNewDocument {
+    pub source: String,
+}
+
+impl CdpCommand for AddScriptToEvaluateOnNewDocument {
+    const METHOD: &'static str = "Page.addScriptToEvaluateOnNewDocument";
+    // The returned script identifier is not needed, so it is ignored
+    type Response = Empty;
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reload {
+    pub ignore_cache: bool,
+}
+
+impl CdpCommand for Reload {
+    const METHOD: &'static str = "Page.reload";
+    type Response = Empty;
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetLayoutMetrics {}
+
+impl CdpCommand for GetLayoutMetrics {
+    const METHOD: &'static str = "Page.getLayoutMetrics";
+    const IDEMPOTENT: bool = true;
+    type Response = GetLayoutMetricsResponse;
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetLayoutMetricsResponse {
+    pub css_content_size: Rect,
+}
+
+// Only the size of the rect is used, its origin is always 0,0
+#[derive(Debug, Deserialize)]
+pub struct Rect {
+    pub width: f64,
+    pub height: f64,
+}
+
+// Area of the page to capture, in CSS pixels
+#[derive(Debug, Serialize)]
+pub struct Viewport {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub scale: f64,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureScreenshot {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clip: Option<Viewport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capture_beyond_viewport: Option<bool>,
+}
+
+impl CdpCommand for CaptureScreenshot {
+    const METHOD: &'static str = "Page.captureScreenshot";
+    const IDEMPOTENT: bool = true;
+    type Response = CaptureScreenshotResponse;
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CaptureScreenshotResponse {
+    // Base64-encoded image
+    pub data: String,
+}
+
+// Paper sizes and margins are in inches
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintToPdf {
+    pub landscape: bool,
+    pub print_background: bool,
+    pub paper_width: f64,
+    pub paper_height: f64,
+    pub margin_top: f64,
+    pub margin_bottom: f64,
+    pub margin_left: f64,
+    pub margin_right: f64,
+}
+
+impl CdpCommand for PrintToPdf {
+    const METHOD: &'static str = "Page.printToPDF";
+    const IDEMPOTENT: bool = true;
+    type Response = PrintToPdfResponse;
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrintToPdfResponse {
+    // Base64-encoded PDF
+    pub data: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CaptureSnapshot {
+    pub format: String,
+}
+
+impl CdpCommand for CaptureSnapshot {
+    const METHOD: &'static str = "Page.captureSnapshot";
+    const IDEMPOTENT: bool = true;
+    type Response = CaptureSnapshotResponse;
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CaptureSnapshotResponse {
+    // The serialized snapshot, as text
+    pub data: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Navigate {
+    pub url: String,
+}
+
+impl CdpCommand for Navigate {
+    const METHOD: &'static str = "Page.navigate";
+    type Response = NavigateResponse;
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NavigateResponse {
+    pub loader_id: Option<String>, // Missing for same-document navigations
+    pub error_text: Option<String>, // e.g. "net::ERR_NAME_NOT_RESOLVED"
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetNavigationHistory {}
+
+impl CdpCommand for GetNavigationHistory {
+    const METHOD: &'static str = "Page.getNavigationHistory";
+    const IDEMPOTENT: bool = true;
+    type Response = GetNavigationHistoryResponse;
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNavigationHistoryResponse {
+    pub current_index: usize,
+    pub entries: Vec<NavigationEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NavigationEntry {
+    pub id: i64,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NavigateToHistoryEntry {
+    pub entry_id: i64,
+}
+
+impl CdpCommand for NavigateToHistoryEntry {
+    const METHOD: &'static str = "Page.navigateToHistoryEntry";
+    type Response = Empty;
+}
+
+#[derive(Debug, Serialize)]
+pub struct SetLifecycleEventsEnabled {
+    pub enabled: bool,
+}
+
+impl CdpCommand for SetLifecycleEventsEnabled {
+    const METHOD: &'static str = "Page.setLifecycleEventsEnabled";
+    const IDEMPOTENT: bool = true;
+    type Response = Empty;
+}
+
+#[derive(Debug, Serialize)]
+pub struct GetFrameTree {}
+
+impl CdpCommand for GetFrameTree {
+    const METHOD: &'static str = "Page.getFrameTree";
+    const IDEMPOTENT: bool = true;
+    type Response = GetFrameTreeResponse;
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetFrameTreeResponse {
+    pub frame_tree: FrameTree,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrameTree {
+    pub frame: Frame,
+    #[serde(default)]
+    pub child_frames: Vec<FrameTree>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Frame {
+    pub id: String,
+    pub loader_id: String,
+    pub url: String,
+}
+
+// Event params
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifecycleEvent {
+    pub frame_id: String,
+    pub loader_id: String,
+    pub name: String, // "init", "DOMContentLoaded", "load", "networkIdle", ...
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FrameNavigated {
+    pub frame: Frame,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NavigatedWithinDocument {
+    pub frame_id: String,
+}
@@ -0,0 +1,160 @@
+use crate::chromium::cdp::CdpError;
+use crate::chromium::protocol::{CdpCommand, Empty};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize)]
+pub struct Enable {}
+
+impl CdpCommand for Enable {
+    const METHOD: &'static str = "Runtime.enable";
+    const IDEMPOTENT: bool = true;
+    type Response = Empty;
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Evaluate {
+    pub expression: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_id: Option<i64>, // The target's main frame when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_by_value: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub await_promise: Option<bool>,
+}
+
+impl Evaluate {
+    pub fn returning_value(expression: &str) -> Self {
+        Self {
+            expression: expression.to_string(),
+            return_by_value: Some(true),
+            ..Default::default()
+        }
+    }
+}
+
+impl CdpCommand for Evaluate {
+    const METHOD: &'static str = "Runtime.evaluate";
+    type Response = EvaluateResponse;
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluateResponse {
+    pub result: RemoteObject,
+    pub exception_details: Option<ExceptionDetails>,
+}
+
+impl EvaluateResponse {
+    // The returned value, or the exception the script threw
+    pub fn into_value<T: DeserializeOwned>(self) -> Result<T, CdpError> {
+        if let Some(exception) = self.exception_details {
+            return Err(CdpError::Exception(exception.message()));
+        }
+
+        let value = self.result.value.unwrap_or(serde_json::Value::Null);
+        serde_json::from_value(value.clone()).map_err(|e| {
+            CdpError::Serialization(format!("Failed to parse result: {} - Raw result: {}", e, value))
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteObject {
+    pub value: Option<serde_json::Value>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExceptionDetails {
+    pub text: String,
+    pub line_number: i64,
+    pub column_number: i64,
+    pub url: Option<String>,
+    pub exception: Option<RemoteObject>,
+}
+
+impl ExceptionDetails {
+    pub fn message(&self) -> String {
+        let description = self
+            .exception
+            .as_ref()
+            .and_then(|exception| exception.description.clone())
+            .unwrap_or_else(|| self.text.clone());
+        format!("{} (line {}, column {})", description, self.line_number, self.column_number)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AddBinding {
+    pub name: String,
+}
+
+impl CdpCommand for AddBinding {
+    const METHOD: &'static str = "Runtime.addBinding";
+    type Response = Empty;
+}
+
+// Event params; `timestamp` is milliseconds since the epoch
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsoleApiCalled {
+    #[serde(rename = "type")]
+    pub call_type: String, // "log", "info", "warning", "error", "debug", "assert", ...
+    pub args: Vec<RemoteObject>,
+    pub timestamp: f64,
+    pub stack_trace: Option<StackTrace>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackTrace {
+    pub call_frames: Vec<CallFrame>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    pub url: String,
+    pub line_number: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExceptionThrown {
+    pub timestamp: f64,
+    pub exception_details: ExceptionDetails,
+}
+
+// Event params
+#[derive(Debug, Deserialize)]
+pub struct ExecutionContextCreated {
+    pub context: ExecutionContextDescription,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionContextDescription {
+    pub id: i64,
+    pub aux_data: Option<serde_json::Value>, // {frameId, isDefault, type}
+}
+
+impl ExecutionContextDescription {
+    // Frame this is the main world of; None for isolated worlds and workers
+    pub fn default_frame_id(&self) -> Option<&str> {
+        let aux_data = self.aux_data.as_ref()?;
+        if !aux_data.get("isDefault")?.as_bool()? {
+            return None;
+        }
+        aux_data.get("frameId")?.as_str()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionContextDestroyed {
+    pub execution_context_id: i64,
+}
@@ -0,0 +1,13 @@
+use crate::chromium::protocol::{CdpCommand, Empty};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct SetDiscoverTargets {
+    pub discover: bool,
+}
+
+impl CdpCommand for SetDiscoverTargets {
+    const METHOD: &'static str = "Target.setDiscoverTargets";
+    const IDEMPOTENT: bool = true;
+    type Response = Empty;
+}
@@ -0,0 +1,266 @@
+use crate::chromium::cdp::CallPolicy;
+use crate::chromium::console::spawn_console_capture;
+use crate::chromium::lib::{check_chrome_devtools, ChromeSession};
+use crate::chromium::process::spawn_process_monitor;
+use crate::chromium::tabs::spawn_active_target_tracker;
+use crate::error::CommandError;
+use crate::settings::load_settings;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use tauri::{AppHandle, Manager, State};
+
+// File in the app's config dir listing the sessions to reattach on the next start
+const SESSIONS_FILE: &str = "chrome_sessions.json";
+
+// Every Chrome session the app controls, kept in Tauri's managed state.
+// Sessions are cheap to clone, so callers get a copy and the lock is never
+// held across an await.
+#[derive(Debug)]
+pub struct SessionRegistry {
+    sessions: RwLock<HashMap<String, ChromeSession>>,
+    store: Option<PathBuf>, // Where the sessions are saved; nothing is saved without it
+    call_policy: RwLock<CallPolicy>, // CDP call defaults from the app settings
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub label: Option<String>,
+    pub debug_port: u16,
+    pub browser: Option<String>,
+    pub owned: bool, // Whether the app launched this Chrome and controls its lifetime
+}
+
+impl SessionRegistry {
+    pub fn new(app: &AppHandle) -> Self {
+        let store = match app.path().app_config_dir() {
+            Ok(dir) => Some(dir.join(SESSIONS_FILE)),
+            Err(e) => {
+                println!("Failed to locate app config dir, sessions won't be saved: {}", e);
+                None
+            }
+        };
+
+        Self {
+            sessions: RwLock::default(),
+            store,
+            call_policy: RwLock::new(load_settings(app).call_policy()),
+        }
+    }
+
+    pub fn get(&self, session_id: &str) -> Result<ChromeSession, CommandError> {
+        self.sessions
+            .read()
+            .unwrap()
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| session_not_found(session_id))
+    }
+
+    pub fn insert(&self, session: ChromeSession) {
+        session.connections.set_call_policy(self.call_policy());
+        let mut sessions = self.sessions.write().unwrap();
+        sessions.insert(session.session_id.clone(), session);
+        self.save(&sessions);
+    }
+
+    pub fn remove(&self, session_id: &str) -> Result<ChromeSession, CommandError> {
+        let mut sessions = self.sessions.write().unwrap();
+        let session = sessions
+            .remove(session_id)
+            .ok_or_else(|| session_not_found(session_id))?;
+        self.save(&sessions);
+        Ok(session)
+    }
+
+    pub fn list(&self) -> Vec<ChromeSession> {
+        self.sessions.read().unwrap().values().cloned().collect()
+    }
+
+    pub fn rename(&self, session_id: &str, label: Option<String>) -> Result<ChromeSession, CommandError> {
+        let mut sessions = self.sessions.write().unwrap();
+        let session = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| session_not_found(session_id))?;
+        session.label = label;
+        let session = session.clone();
+        self.save(&sessions);
+        Ok(session)
+    }
+
+    pub fn call_policy(&self) -> CallPolicy {
+        *self.call_policy.read().unwrap()
+    }
+
+    // New defaults for every session, open or not
+    pub fn set_call_policy(&self, policy: CallPolicy) {
+        *self.call_policy.write().unwrap() = policy;
+        for session in self.sessions.read().unwrap().values() {
+            session.connections.set_call_policy(policy);
+        }
+    }
+
+    // Written while the caller holds the write lock, so saves never interleave
+    fn save(&self, sessions: &HashMap<String, ChromeSession>) {
+        let Some(path) = &self.store else {
+            return;
+        };
+
+        let result = (|| {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)
+                    .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            }
+            let saved: Vec<&ChromeSession> = sessions.values().collect();
+            let contents = serde_json::to_string_pretty(&saved)
+                .map_err(|e| format!("Failed to serialize sessions: {}", e))?;
+            std::fs::write(path, contents)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+        })();
+
+        if let Err(e) = result {
+            println!("Failed to save Chrome sessions: {}", e);
+        }
+    }
+
+    fn read_saved(&self) -> Result<Vec<ChromeSession>, String> {
+        let Some(path) = self.store.as_ref().filter(|path| path.exists()) else {
+            return Ok(Vec::new());
+        };
+
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&contents).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+    }
+}
+
+fn session_not_found(session_id: &str) -> CommandError {
+    CommandError::SessionNotFound {
+        session_id: session_id.to_string(),
+    }
+}
+
+// Reattach to the browsers saved sessions were using, if they are still running.
+// Sessions whose browser is gone are dropped from the file, along with their
+// temporary profile. Browsers an earlier run launched stay owned, with their
+// relaunch setting and temporary profile.
+pub fn restore_sessions(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let registry = app.state::<SessionRegistry>();
+
+        let saved = match registry.read_saved() {
+            Ok(saved) => saved,
+            Err(e) => {
+                println!("Failed to restore Chrome sessions: {}", e);
+                return;
+            }
+        };
+
+        let mut reachable = Vec::new();
+        for mut session in saved {
+            match check_chrome_devtools(session.debug_port).await {
+                Ok(targets) => {
+                    println!(
+                        "Reattached to Chrome session {} on port {} with {} targets",
+                        session.session_id,
+                        session.debug_port,
+                        targets.len()
+                    );
+                    session.connections.set_call_policy(registry.call_policy());
+                    session.read_browser_version().await;
+                    reachable.push(session);
+                }
+                Err(e) => {
+                    println!(
+                        "Dropping Chrome session {} on port {}: {}",
+                        session.session_id, session.debug_port, e
+                    );
+                    // Its browser is gone, nothing uses the temporary profile anymore
+                    session.process.remove_profile().await;
+                }
+            }
+        }
+
+        // Sessions opened while the saved ones were checked are kept as they are
+        let mut restored = Vec::new();
+        {
+            let mut sessions = registry.sessions.write().unwrap();
+            for session in reachable {
+                if sessions.values().any(|s| s.debug_port == session.debug_port) {
+                    println!(
+                        "Dropping Chrome session {}, port {} already has a session",
+                        session.session_id, session.debug_port
+                    );
+                    continue;
+                }
+                sessions
+                    .entry(session.session_id.clone())
+                    .or_insert_with(|| session.clone());
+                restored.push(session);
+            }
+            registry.save(&sessions);
+        }
+
+        for session in &restored {
+            spawn_active_target_tracker(session);
+            spawn_console_capture(session);
+            if session.process.is_owned() {
+                spawn_process_monitor(app.clone(), session);
+            }
+        }
+    });
+}
+
+impl From<&ChromeSession> for SessionSummary {
+    fn from(session: &ChromeSession) -> Self {
+        Self {
+            session_id: session.session_id.clone(),
+            label: session.label.clone(),
+            debug_port: session.debug_port,
+            browser: session.browser.clone(),
+            owned: session.process.is_owned(),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn chrome_list_sessions(sessions: State<'_, SessionRegistry>) -> Vec<SessionSummary> {
+    let mut summaries: Vec<SessionSummary> = sessions
+        .list()
+        .iter()
+        .map(SessionSummary::from)
+        .collect();
+    summaries.sort_by_key(|summary| summary.debug_port);
+    summaries
+}
+
+#[tauri::command]
+pub fn chrome_rename_session(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    label: Option<String>,
+) -> Result<SessionSummary, CommandError> {
+    // An empty name clears the label
+    let label = label
+        .map(|label| label.trim().to_string())
+        .filter(|label| !label.is_empty());
+
+    let session = sessions.rename(&session_id, label)?;
+    println!("Renamed Chrome session {} to {:?}", session_id, session.label);
+    Ok(SessionSummary::from(&session))
+}
+
+// Abort whatever the session is waiting on, such as a slow script or a stuck capture.
+// The aborted commands fail with a "cancelled" error; the session stays usable.
+#[tauri::command]
+pub fn chrome_cancel(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+) -> Result<(), CommandError> {
+    let session = sessions.get(&session_id)?;
+    session.connections.cancel();
+    println!("Cancelled in-flight operations of Chrome session {}", session_id);
+    Ok(())
+}
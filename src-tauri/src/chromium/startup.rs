@@ -0,0 +1,197 @@
+use crate::chromium::lib::{check_chrome_devtools, is_port_in_use, ChromeTarget};
+use crate::chromium::sessions::SessionRegistry;
+use crate::error::CommandError;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::Child;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::State;
+use tokio::time::sleep;
+
+// Line Chrome prints on stderr once its DevTools endpoint accepts connections
+const DEVTOOLS_LISTENING_PREFIX: &str = "DevTools listening on ";
+// File Chrome writes into the user-data dir with the port and browser endpoint
+pub const DEVTOOLS_ACTIVE_PORT_FILE: &str = "DevToolsActivePort";
+
+// Only the latest lines are kept, older ones are dropped so Chrome never blocks on its output
+const STARTUP_LOG_LINES: usize = 200;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(50);
+const MAX_BACKOFF: Duration = Duration::from_secs(1);
+pub const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+// stderr of a launched browser, kept for diagnosing slow or failed starts
+#[derive(Debug, Clone, Default)]
+pub struct StartupLog(Arc<Mutex<LogLines>>);
+
+#[derive(Debug, Default)]
+struct LogLines {
+    lines: VecDeque<String>,
+    // Printed early, so it is kept apart from the lines that roll over
+    devtools_url: Option<String>,
+}
+
+impl StartupLog {
+    fn push(&self, line: String) {
+        let mut log = self.0.lock().unwrap();
+        if let Some(url) = line.strip_prefix(DEVTOOLS_LISTENING_PREFIX) {
+            log.devtools_url = Some(url.trim().to_string());
+        }
+        if log.lines.len() == STARTUP_LOG_LINES {
+            log.lines.pop_front();
+        }
+        log.lines.push_back(line);
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.0.lock().unwrap().lines.iter().cloned().collect()
+    }
+
+    fn tail(&self, count: usize) -> String {
+        let log = self.0.lock().unwrap();
+        let skip = log.lines.len().saturating_sub(count);
+        log.lines
+            .iter()
+            .skip(skip)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn devtools_url(&self) -> Option<String> {
+        self.0.lock().unwrap().devtools_url.clone()
+    }
+}
+
+#[tauri::command]
+pub fn chrome_get_startup_log(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+) -> Result<Vec<String>, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    session
+        .process
+        .startup_log()
+        .map(|log| log.lines())
+        .ok_or_else(|| "Session did not launch its browser".into())
+}
+
+// Read the browser's stderr on a thread of its own for as long as it runs
+pub fn capture_stderr(child: &mut Child) -> StartupLog {
+    let log = StartupLog::default();
+
+    if let Some(stderr) = child.stderr.take() {
+        let log = log.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines() {
+                match line {
+                    Ok(line) => log.push(line),
+                    Err(_) => break,
+                }
+            }
+        });
+    }
+
+    log
+}
+
+// Where Chrome will announce its endpoint in `user_data_dir`; a file left by an
+// earlier run is removed so it can't be mistaken for this one
+pub fn reset_active_port_file(user_data_dir: Option<&str>) -> Option<PathBuf> {
+    let path = Path::new(user_data_dir?).join(DEVTOOLS_ACTIVE_PORT_FILE);
+    let _ = std::fs::remove_file(&path);
+    Some(path)
+}
+
+// Wait until the launched browser announces DevTools and answers on its port,
+// backing off between checks and giving up after `timeout`
+pub async fn wait_for_devtools(
+    child: &mut Child,
+    debug_port: u16,
+    active_port_file: Option<&Path>,
+    log: &StartupLog,
+    timeout: Duration,
+) -> Result<Vec<ChromeTarget>, CommandError> {
+    let started = Instant::now();
+    let mut backoff = INITIAL_BACKOFF;
+    let mut last_error = "DevTools endpoint not announced yet".to_string();
+
+    loop {
+        if let Ok(Some(status)) = child.try_wait() {
+            // A clean exit usually means another browser already owns this profile
+            let hint = if status.success() {
+                " - another browser may already be running with this profile"
+            } else {
+                ""
+            };
+            return Err(format!(
+                "Chrome exited during startup ({}){}\n{}",
+                status,
+                hint,
+                log.tail(20)
+            )
+            .into());
+        }
+
+        let announced = log.devtools_url().is_some()
+            || active_port_file.is_some_and(|file| file.is_file())
+            || is_port_in_use(debug_port);
+
+        if announced {
+            match check_chrome_devtools(debug_port).await {
+                Ok(targets) => {
+                    println!(
+                        "Chrome DevTools is responsive with {} targets after {} ms",
+                        targets.len(),
+                        started.elapsed().as_millis()
+                    );
+                    return Ok(targets);
+                }
+                Err(e) => last_error = e.to_string(),
+            }
+        }
+
+        let elapsed = started.elapsed();
+        if elapsed >= timeout {
+            return Err(CommandError::Timeout {
+                operation: format!(
+                    "Chrome did not become ready within {} s: {}\n{}",
+                    timeout.as_secs(),
+                    last_error,
+                    log.tail(20)
+                ),
+            });
+        }
+
+        sleep(backoff.min(timeout - elapsed)).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn startup_log_keeps_the_latest_lines() {
+        let log = StartupLog::default();
+        log.push(format!(
+            "{}ws://127.0.0.1:9222/devtools/browser/abc",
+            DEVTOOLS_LISTENING_PREFIX
+        ));
+        for i in 0..STARTUP_LOG_LINES + 10 {
+            log.push(format!("line {}", i));
+        }
+
+        let lines = log.lines();
+        assert_eq!(lines.len(), STARTUP_LOG_LINES);
+        assert_eq!(lines[0], "line 10");
+        assert_eq!(log.tail(2), "line 208\nline 209");
+        assert_eq!(
+            log.devtools_url().as_deref(),
+            Some("ws://127.0.0.1:9222/devtools/browser/abc")
+        );
+    }
+}
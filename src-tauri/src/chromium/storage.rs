@@ -0,0 +1,263 @@
+use crate::chromium::capture::default_export_path;
+use crate::chromium::lib::{cdp_client, ChromeSession};
+use crate::chromium::protocol::network::{self, Cookie};
+use crate::chromium::protocol::CdpClient;
+use crate::chromium::sessions::SessionRegistry;
+use crate::chromium::tabs::{devtools_tab_request, list_tabs, open_tab, resolve_target};
+use crate::error::CommandError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::State;
+use tokio::time::sleep;
+
+// Read both storages of the page's origin; null where storage is not available
+const STORAGE_DUMP_SCRIPT: &str = r#"
+(function() {
+    const dump = (storage) => {
+        const items = {};
+        for (let i = 0; i < storage.length; i++) {
+            const key = storage.key(i);
+            items[key] = storage.getItem(key);
+        }
+        return items;
+    };
+
+    try {
+        return {
+            origin: location.origin,
+            local_storage: dump(window.localStorage),
+            session_storage: dump(window.sessionStorage)
+        };
+    } catch (e) {
+        return null;
+    }
+})()
+"#;
+
+// How long a temporary tab gets to load the origin it restores storage into
+const ORIGIN_LOAD_ATTEMPTS: u32 = 20;
+const ORIGIN_LOAD_INTERVAL: Duration = Duration::from_millis(500);
+
+// Cookies and web storage of a session, as written to and read from disk
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StorageState {
+    pub cookies: Vec<Cookie>,
+    pub origins: Vec<OriginStorage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OriginStorage {
+    pub origin: String,
+    #[serde(default)]
+    pub local_storage: HashMap<String, String>,
+    #[serde(default)]
+    pub session_storage: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StorageSummary {
+    pub path: String,
+    pub cookie_count: usize,
+    pub origin_count: usize,
+    pub skipped: Vec<String>, // Pages or origins whose storage could not be read or restored
+}
+
+#[tauri::command]
+pub async fn chrome_export_storage(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    path: Option<String>,
+) -> Result<StorageSummary, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let target = resolve_target(&session, None).await?;
+    let client = cdp_client(&session, &target).await?;
+    let cookies = client.execute(network::GetAllCookies {}).await?.cookies;
+
+    // Web storage is only reachable from a page of its origin, so read it from the open tabs
+    let mut origins: Vec<OriginStorage> = Vec::new();
+    let mut skipped = Vec::new();
+    for tab in list_tabs(&session).await? {
+        if !tab.url.starts_with("http://") && !tab.url.starts_with("https://") {
+            continue;
+        }
+
+        let client = cdp_client(&session, &tab).await?;
+        let storage = match client
+            .evaluate_read_only::<Option<OriginStorage>>(None, STORAGE_DUMP_SCRIPT)
+            .await
+        {
+            Ok(Some(storage)) => storage,
+            Ok(None) | Err(_) => {
+                skipped.push(tab.url.clone());
+                continue;
+            }
+        };
+
+        // sessionStorage differs between tabs of one origin, so merge what each tab holds
+        match origins.iter_mut().find(|o| o.origin == storage.origin) {
+            Some(existing) => {
+                existing.local_storage.extend(storage.local_storage);
+                existing.session_storage.extend(storage.session_storage);
+            }
+            None => origins.push(storage),
+        }
+    }
+
+    let state = StorageState { cookies, origins };
+    let contents = serde_json::to_string_pretty(&state)
+        .map_err(|e| format!("Failed to serialize storage: {}", e))?;
+
+    let path = path.unwrap_or_else(|| default_export_path("json"));
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+    println!(
+        "Exported {} cookies and storage of {} origins to {}",
+        state.cookies.len(),
+        state.origins.len(),
+        path
+    );
+    Ok(StorageSummary {
+        path,
+        cookie_count: state.cookies.len(),
+        origin_count: state.origins.len(),
+        skipped,
+    })
+}
+
+#[tauri::command]
+pub async fn chrome_import_storage(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    path: String,
+) -> Result<StorageSummary, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read storage file {}: {}", path, e))?;
+    let state: StorageState = serde_json::from_str(&contents)
+        .map_err(|e| format!("Failed to parse storage file {}: {}", path, e))?;
+
+    let cookie_count = state.cookies.len();
+    if cookie_count > 0 {
+        let target = resolve_target(&session, None).await?;
+        let client = cdp_client(&session, &target).await?;
+        client
+            .execute(network::SetCookies {
+                cookies: state.cookies.into_iter().map(Into::into).collect(),
+            })
+            .await?;
+    }
+
+    let mut skipped = Vec::new();
+    for storage in &state.origins {
+        if let Err(e) = restore_origin(&session, storage).await {
+            println!("Failed to restore storage of {}: {}", storage.origin, e);
+            skipped.push(storage.origin.clone());
+        }
+    }
+
+    println!(
+        "Imported {} cookies and storage of {} origins from {}",
+        cookie_count,
+        state.origins.len() - skipped.len(),
+        path
+    );
+    Ok(StorageSummary {
+        path,
+        cookie_count,
+        origin_count: state.origins.len() - skipped.len(),
+        skipped,
+    })
+}
+
+// Write an origin's items from one of its open tabs, or from a temporary tab
+// when none is open. sessionStorage only survives in tabs that stay open, so
+// without one the origin is reported as not restored.
+async fn restore_origin(session: &ChromeSession, storage: &OriginStorage) -> Result<(), String> {
+    let open_tabs: Vec<_> = list_tabs(session)
+        .await?
+        .into_iter()
+        .filter(|tab| url_origin(&tab.url).as_deref() == Some(storage.origin.as_str()))
+        .collect();
+
+    if open_tabs.is_empty() {
+        if !storage.local_storage.is_empty() {
+            let tab = open_tab(session, &storage.origin).await?;
+            let result = async {
+                let client = cdp_client(session, &tab).await?;
+                wait_for_origin(&client, &storage.origin).await?;
+                write_storage(&client, "localStorage", &storage.local_storage).await
+            }
+            .await;
+
+            // A failed write is the error worth reporting, not a failed close after it
+            let closed = devtools_tab_request(session.debug_port, "close", &tab.id).await;
+            result?;
+            closed?;
+        }
+
+        if !storage.session_storage.is_empty() {
+            return Err(format!(
+                "sessionStorage of {} needs an open tab of that origin",
+                storage.origin
+            ));
+        }
+        return Ok(());
+    }
+
+    for tab in &open_tabs {
+        let client = cdp_client(session, tab).await?;
+        write_storage(&client, "localStorage", &storage.local_storage).await?;
+        write_storage(&client, "sessionStorage", &storage.session_storage).await?;
+    }
+
+    Ok(())
+}
+
+fn url_origin(url: &str) -> Option<String> {
+    reqwest::Url::parse(url)
+        .ok()
+        .map(|url| url.origin().ascii_serialization())
+}
+
+async fn wait_for_origin(client: &CdpClient, origin: &str) -> Result<(), String> {
+    let expression = format!(
+        "document.readyState !== 'loading' && location.origin === {}",
+        serde_json::to_string(origin).unwrap_or_default()
+    );
+
+    for _ in 0..ORIGIN_LOAD_ATTEMPTS {
+        if client
+            .evaluate_read_only::<bool>(None, &expression)
+            .await
+            .unwrap_or(false)
+        {
+            return Ok(());
+        }
+        sleep(ORIGIN_LOAD_INTERVAL).await;
+    }
+
+    Err(format!("Timed out waiting for {} to load", origin))
+}
+
+async fn write_storage(
+    client: &CdpClient,
+    storage: &str,
+    items: &HashMap<String, String>,
+) -> Result<(), String> {
+    if items.is_empty() {
+        return Ok(());
+    }
+
+    let items = serde_json::to_string(items)
+        .map_err(|e| format!("Failed to serialize {} items: {}", storage, e))?;
+    let script = format!(
+        "(function(items) {{ for (const [key, value] of Object.entries(items)) {{ window.{}.setItem(key, value); }} return true; }})({})",
+        storage, items
+    );
+
+    client.evaluate::<bool>(&script).await?;
+    Ok(())
+}
@@ -0,0 +1,450 @@
+use crate::chromium::cdp::{page_websocket_url, CdpConnectionPool, CdpEvent};
+use crate::chromium::lib::{
+    cdp_client, check_chrome_devtools, fetch_browser_version, ChromeSession, ChromeTarget,
+};
+use crate::chromium::protocol::{page, runtime, target};
+use crate::chromium::sessions::SessionRegistry;
+use crate::error::CommandError;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+use tokio::sync::broadcast::error::RecvError;
+
+// Name of the binding pages call when they become the visible, focused tab
+const FOCUS_BINDING: &str = "__nebuleaTabFocused";
+
+// Injected into every page so it reports when the user brings it to the front
+const FOCUS_TRACKING_SCRIPT: &str = r#"
+(function() {
+    if (window.__nebuleaFocusTracked) return;
+    window.__nebuleaFocusTracked = true;
+
+    const report = () => {
+        if (document.visibilityState === 'visible' && typeof window.__nebuleaTabFocused === 'function') {
+            window.__nebuleaTabFocused('focused');
+        }
+    };
+
+    document.addEventListener('visibilitychange', report);
+    window.addEventListener('focus', report);
+    report();
+})();
+"#;
+
+// Id of the tab the user has focused, as last reported by the browser
+#[derive(Debug, Clone, Default)]
+pub struct ActiveTarget(Arc<Mutex<Option<String>>>);
+
+impl ActiveTarget {
+    pub fn get(&self) -> Option<String> {
+        self.0.lock().unwrap().clone()
+    }
+
+    pub fn set(&self, target_id: &str) {
+        *self.0.lock().unwrap() = Some(target_id.to_string());
+    }
+
+    fn clear_if(&self, target_id: &str) {
+        let mut active = self.0.lock().unwrap();
+        if active.as_deref() == Some(target_id) {
+            *active = None;
+        }
+    }
+}
+
+// Order in which the session first saw its tabs, so tab numbers stay stable
+// while the DevTools list reorders itself by recent activity
+#[derive(Debug, Clone, Default)]
+pub struct TabOrder(Arc<Mutex<Vec<String>>>);
+
+impl TabOrder {
+    fn sort(&self, mut tabs: Vec<ChromeTarget>) -> Vec<ChromeTarget> {
+        let mut order = self.0.lock().unwrap();
+
+        order.retain(|id| tabs.iter().any(|t| &t.id == id));
+        // The DevTools list puts the most recent tab first, so unseen tabs go in reversed
+        for tab in tabs.iter().rev() {
+            if !order.contains(&tab.id) {
+                order.push(tab.id.clone());
+            }
+        }
+
+        tabs.sort_by_key(|t| order.iter().position(|id| id == &t.id));
+        tabs
+    }
+}
+
+// Pick the target a command runs on: the requested one, else the focused tab,
+// else the first regular page
+pub async fn resolve_target(
+    session: &ChromeSession,
+    target_id: Option<&str>,
+) -> Result<ChromeTarget, CommandError> {
+    let targets = check_chrome_devtools(session.debug_port).await?;
+
+    if let Some(target_id) = target_id {
+        return targets
+            .into_iter()
+            .find(|t| t.id == target_id)
+            .ok_or_else(|| CommandError::NoTarget {
+                target_id: Some(target_id.to_string()),
+            });
+    }
+
+    if let Some(active_id) = session.active_target.get() {
+        if let Some(target) = targets.iter().find(|t| t.id == active_id) {
+            return Ok(target.clone());
+        }
+    }
+
+    targets
+        .iter()
+        .find(|t| t.target_type == "page" && !t.url.starts_with("chrome-extension://"))
+        .or_else(|| targets.iter().find(|t| t.target_type == "page"))
+        .or_else(|| targets.first())
+        .cloned()
+        .ok_or(CommandError::NoTarget { target_id: None })
+}
+
+#[tauri::command]
+pub async fn chrome_get_active_target(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+) -> Result<ChromeTarget, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    resolve_target(&session, None).await
+}
+
+// Regular browser tabs of the session, in stable order
+pub async fn list_tabs(session: &ChromeSession) -> Result<Vec<ChromeTarget>, CommandError> {
+    let targets = check_chrome_devtools(session.debug_port).await?;
+
+    let tabs = targets
+        .into_iter()
+        .filter(|t| t.target_type == "page" && !t.url.starts_with("chrome-extension://"))
+        .collect();
+
+    Ok(session.tab_order.sort(tabs))
+}
+
+#[tauri::command]
+pub async fn chrome_list_tabs(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+) -> Result<Vec<ChromeTarget>, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    list_tabs(&session).await
+}
+
+#[tauri::command]
+pub async fn chrome_new_tab(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    url: Option<String>,
+) -> Result<ChromeTarget, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let url = url.unwrap_or_else(|| "about:blank".to_string());
+    let target = open_tab(&session, &url).await?;
+
+    session.active_target.set(&target.id);
+    Ok(target)
+}
+
+// Open `url` in a new tab through the DevTools HTTP endpoint
+pub async fn open_tab(session: &ChromeSession, url: &str) -> Result<ChromeTarget, CommandError> {
+    let endpoint = format!(
+        "http://127.0.0.1:{}/json/new?{}",
+        session.debug_port,
+        urlencoding::encode(url)
+    );
+
+    // Recent Chrome versions only accept PUT on this endpoint
+    let response = reqwest::Client::new()
+        .put(&endpoint)
+        .send()
+        .await
+        .map_err(|e| CommandError::DevToolsUnreachable {
+            port: Some(session.debug_port),
+            reason: format!("Failed to open new tab: {}", e),
+        })?;
+
+    if !response.status().is_success() {
+        return Err(format!("Failed to open new tab: DevTools returned {}", response.status()).into());
+    }
+
+    response
+        .json::<ChromeTarget>()
+        .await
+        .map_err(|e| CommandError::ParseFailed {
+            input: endpoint,
+            message: format!("Failed to parse new tab: {}", e),
+        })
+}
+
+#[tauri::command]
+pub async fn chrome_close_tab(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    target_id: Option<String>,
+) -> Result<String, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let target = resolve_target(&session, target_id.as_deref()).await?;
+    devtools_tab_request(session.debug_port, "close", &target.id).await?;
+    session.active_target.clear_if(&target.id);
+
+    Ok(format!("Closed tab: {}", target.title))
+}
+
+#[tauri::command]
+pub async fn chrome_activate_tab(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    target_id: String,
+) -> Result<ChromeTarget, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let target = resolve_target(&session, Some(&target_id)).await?;
+    devtools_tab_request(session.debug_port, "activate", &target.id).await?;
+    session.active_target.set(&target.id);
+
+    Ok(target)
+}
+
+#[tauri::command]
+pub async fn chrome_reload_tab(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    target_id: Option<String>,
+    ignore_cache: Option<bool>,
+) -> Result<String, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let target = resolve_target(&session, target_id.as_deref()).await?;
+    let client = cdp_client(&session, &target).await?;
+    client
+        .execute(page::Reload {
+            ignore_cache: ignore_cache.unwrap_or(false),
+        })
+        .await?;
+
+    Ok(format!("Reloaded tab: {}", target.title))
+}
+
+// Call one of the DevTools `/json/<action>/<id>` endpoints
+pub async fn devtools_tab_request(
+    debug_port: u16,
+    action: &str,
+    target_id: &str,
+) -> Result<(), String> {
+    let endpoint = format!("http://127.0.0.1:{}/json/{}/{}", debug_port, action, target_id);
+
+    let response = reqwest::get(&endpoint)
+        .await
+        .map_err(|e| format!("Failed to {} tab: {}", action, e))?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        let error_text = response.text().await.unwrap_or_default();
+        Err(format!("Failed to {} tab: {}", action, error_text))
+    }
+}
+
+// Follow Target events and focus reports to keep the session's active tab current
+pub fn spawn_active_target_tracker(session: &ChromeSession) {
+    let mut events = session.connections.subscribe_events();
+    let pool = session.connections.downgrade();
+    let active_target = session.active_target.clone();
+    let debug_port = session.debug_port;
+    let session_id = session.session_id.clone();
+
+    tauri::async_runtime::spawn(async move {
+        match pool.upgrade() {
+            Some(pool) => {
+                if let Err(e) = watch_targets(&pool, debug_port).await {
+                    println!("Active tab tracking unavailable for session {}: {}", session_id, e);
+                    return;
+                }
+            }
+            None => return,
+        }
+
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
+
+            let pool = match pool.upgrade() {
+                Some(pool) => pool,
+                None => break,
+            };
+
+            match event.method.as_str() {
+                "Target.targetCreated" => {
+                    if let Some(target_id) = event.created_page_id() {
+                        let websocket_url = page_websocket_url(debug_port, target_id);
+                        tauri::async_runtime::spawn(async move {
+                            if let Err(e) = track_focus(&pool, &websocket_url).await {
+                                println!("Failed to track focus of {}: {}", websocket_url, e);
+                            }
+                        });
+                    }
+                }
+                "Target.targetDestroyed" => {
+                    if let Some(target_id) = event.params.get("targetId").and_then(|v| v.as_str()) {
+                        active_target.clear_if(target_id);
+                    }
+                }
+                "Runtime.bindingCalled" => {
+                    if let Some(target_id) = focused_target(&event) {
+                        active_target.set(target_id);
+                    }
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+// Target that reported being brought to the front, if the event is such a report
+fn focused_target(event: &CdpEvent) -> Option<&str> {
+    let is_focus_report = event.method == "Runtime.bindingCalled"
+        && event.params.get("name").and_then(|v| v.as_str()) == Some(FOCUS_BINDING);
+    is_focus_report.then_some(event.target_id.as_str())
+}
+
+// Turn on target discovery and start tracking focus of the pages already open
+pub async fn watch_targets(pool: &CdpConnectionPool, debug_port: u16) -> Result<(), String> {
+    let browser = fetch_browser_version(debug_port).await?;
+    let browser_client = pool.client(&browser.websocket_debugger_url).await?;
+    browser_client
+        .execute(target::SetDiscoverTargets { discover: true })
+        .await?;
+
+    let targets = check_chrome_devtools(debug_port).await?;
+    for target in targets.iter().filter(|t| t.target_type == "page") {
+        if let Err(e) = track_focus(pool, &target.websocket_debugger_url).await {
+            println!("Failed to track focus of {}: {}", target.url, e);
+        }
+    }
+
+    Ok(())
+}
+
+async fn track_focus(pool: &CdpConnectionPool, websocket_url: &str) -> Result<(), String> {
+    let client = pool.client(websocket_url).await?;
+
+    client.execute(runtime::Enable {}).await?;
+    client
+        .execute(runtime::AddBinding {
+            name: FOCUS_BINDING.to_string(),
+        })
+        .await?;
+    client
+        .execute(page::AddScriptToEvaluateOnNewDocument {
+            source: FOCUS_TRACKING_SCRIPT.to_string(),
+        })
+        .await?;
+    client
+        .evaluate::<serde_json::Value>(FOCUS_TRACKING_SCRIPT)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tab(id: &str) -> ChromeTarget {
+        ChromeTarget {
+            id: id.to_string(),
+            target_type: "page".to_string(),
+            title: String::new(),
+            url: format!("https://example.com/{}", id),
+            websocket_debugger_url: page_websocket_url(9222, id),
+            parent_id: None,
+        }
+    }
+
+    fn ids(tabs: &[ChromeTarget]) -> Vec<&str> {
+        tabs.iter().map(|t| t.id.as_str()).collect()
+    }
+
+    #[test]
+    fn tab_order_starts_with_the_oldest_tab() {
+        let order = TabOrder::default();
+        // DevTools lists the most recently used tab first
+        let sorted = order.sort(vec![tab("c"), tab("b"), tab("a")]);
+        assert_eq!(ids(&sorted), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn tab_order_is_stable_when_devtools_reorders() {
+        let order = TabOrder::default();
+        order.sort(vec![tab("b"), tab("a")]);
+
+        let sorted = order.sort(vec![tab("a"), tab("b")]);
+        assert_eq!(ids(&sorted), ["a", "b"]);
+    }
+
+    #[test]
+    fn tab_order_appends_new_tabs_and_forgets_closed_ones() {
+        let order = TabOrder::default();
+        order.sort(vec![tab("c"), tab("b"), tab("a")]);
+
+        let sorted = order.sort(vec![tab("d"), tab("c"), tab("a")]);
+        assert_eq!(ids(&sorted), ["a", "c", "d"]);
+
+        // A reopened id counts as a new tab
+        let sorted = order.sort(vec![tab("b"), tab("d"), tab("c"), tab("a")]);
+        assert_eq!(ids(&sorted), ["a", "c", "d", "b"]);
+    }
+
+    fn event(method: &str, params: serde_json::Value) -> CdpEvent {
+        CdpEvent {
+            target_id: "tab-1".to_string(),
+            method: method.to_string(),
+            params,
+        }
+    }
+
+    #[test]
+    fn focus_report_names_the_reporting_target() {
+        let report = event(
+            "Runtime.bindingCalled",
+            json!({ "name": FOCUS_BINDING, "payload": "focused", "executionContextId": 1 }),
+        );
+        assert_eq!(focused_target(&report), Some("tab-1"));
+    }
+
+    #[test]
+    fn other_binding_calls_are_not_focus_reports() {
+        let other = event(
+            "Runtime.bindingCalled",
+            json!({ "name": "someOtherBinding", "payload": "focused" }),
+        );
+        assert_eq!(focused_target(&other), None);
+
+        let unrelated = event("Page.loadEventFired", json!({ "name": FOCUS_BINDING }));
+        assert_eq!(focused_target(&unrelated), None);
+    }
+
+    #[test]
+    fn active_target_clears_only_the_closed_tab() {
+        let active = ActiveTarget::default();
+        active.set("tab-1");
+
+        active.clear_if("tab-2");
+        assert_eq!(active.get().as_deref(), Some("tab-1"));
+
+        active.clear_if("tab-1");
+        assert_eq!(active.get(), None);
+    }
+}
@@ -1,9 +1,17 @@
-use crate::chromium::lib::{check_chrome_devtools, send_cdp_message};
+use crate::chromium::cdp::CallOptions;
+use crate::chromium::frames::{frame_origin, page_frames, PageFrame, SkippedFrame};
+use crate::chromium::input::perform_trusted_action;
+use crate::chromium::lib::cdp_client;
+use crate::chromium::protocol::CdpClient;
+use crate::chromium::sessions::SessionRegistry;
+use crate::chromium::tabs::resolve_target;
 use crate::chromium::vimium_script::{
-    generate_element_action_script, VIMIUM_CLEANUP_SCRIPT, VIMIUM_SCRIPT,
+    generate_element_action_script, generate_hint_lookup_script, generate_vimium_script,
+    VIMIUM_CLEANUP_SCRIPT,
 };
-use crate::{get_chrome_sessions, run_async};
+use crate::error::CommandError;
 use serde::{Deserialize, Serialize};
+use tauri::State;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PageElement {
@@ -18,6 +26,7 @@ pub struct PageElement {
     pub height: f64,
     pub visible: bool,
     pub selector: String,
+    pub frame_id: Option<String>, // Iframe the element is in; None for the main frame
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -25,6 +34,7 @@ pub struct PageHints {
     pub elements: Vec<PageElement>,
     pub total_count: usize,
     pub visible_count: usize,
+    pub skipped_frames: Vec<SkippedFrame>, // Iframes whose elements could not be hinted
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -33,143 +43,259 @@ pub struct ElementAction {
     pub action_type: String, // "click", "hover", "focus", "right_click", "fill", "set_value"
     pub modifier_keys: Option<Vec<String>>, // "ctrl", "shift", "alt", "meta"
     pub value: Option<String>, // For fill/set_value operations
+    pub input_mode: Option<String>, // "trusted" (default, CDP input events) or "script" (synthetic DOM events)
+    pub frame_id: Option<String>, // Frame of the hinted element; looked up from the hint when unset
+}
+
+// What the hint script reports for one frame. Coordinates are relative to that frame's page
+#[derive(Debug, Deserialize)]
+struct FrameHints {
+    elements: Vec<PageElement>,
+    scroll_x: f64,
+    scroll_y: f64,
 }
 
 #[tauri::command]
-pub fn chrome_show_page_hints(session_id: String) -> Result<PageHints, String> {
-    run_async(async move {
-        let sessions = get_chrome_sessions();
-        let session = sessions
-            .get(&session_id)
-            .ok_or("Session not found")?
-            .clone();
-
-        // Get current targets
-        let targets = check_chrome_devtools(session.debug_port)
-            .await
-            .map_err(|e| format!("Chrome session is no longer responsive: {}", e))?;
+pub async fn chrome_show_page_hints(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    target_id: Option<String>,
+    call_options: Option<CallOptions>,
+) -> Result<PageHints, CommandError> {
+    let session = sessions.get(&session_id)?;
 
-        // Find the best target to execute script on
-        let target = targets
-            .iter()
-            .find(|t| t.target_type == "page" && !t.url.starts_with("chrome-extension://"))
-            .or_else(|| targets.iter().find(|t| t.target_type == "page"))
-            .or_else(|| targets.first())
-            .ok_or("No suitable target found for script execution")?;
-
-        let params = serde_json::json!({
-            "expression": VIMIUM_SCRIPT,
-            "returnByValue": true
-        });
-
-        match send_cdp_message(&target.websocket_debugger_url, "Runtime.evaluate", params).await {
-            Ok(result) => {
-                if let Some(value) = result.get("value") {
-                    // Try to parse the result as PageHints
-                    match serde_json::from_value::<PageHints>(value.clone()) {
-                        Ok(page_hints) => Ok(page_hints),
-                        Err(e) => Err(format!(
-                            "Failed to parse page hints: {} - Raw result: {}",
-                            e, value
-                        )),
-                    }
-                } else if let Some(result_obj) = result.get("result") {
-                    if let Some(value) = result_obj.get("value") {
-                        match serde_json::from_value::<PageHints>(value.clone()) {
-                            Ok(page_hints) => Ok(page_hints),
-                            Err(e) => Err(format!(
-                                "Failed to parse page hints from result: {} - Raw result: {}",
-                                e, value
-                            )),
-                        }
-                    } else {
-                        Err(format!("No value in result object: {}", result_obj))
-                    }
-                } else {
-                    Err(format!("Unexpected result format: {}", result))
-                }
+    let target = resolve_target(&session, target_id.as_deref()).await?;
+
+    let client = cdp_client(&session, &target)
+        .await?
+        .with_options(call_options);
+
+    let frames = page_frames(&session, &client).await?;
+    let mut skipped_frames = frames.skipped;
+
+    let mut elements: Vec<PageElement> = Vec::new();
+    let mut page_scroll = (0.0, 0.0);
+    // Labels already shown, including those of frames left out of the result
+    let mut next_hint = 0;
+    for frame in &frames.frames {
+        // Each frame continues the labels where the previous one stopped. The script
+        // replaces the hints it showed before, so a timed out run can be retried
+        let script = generate_vimium_script(next_hint);
+        let hints: FrameHints = match frame
+            .client(&client)
+            .evaluate_idempotent(frame.context_id, &script)
+            .await
+        {
+            Ok(hints) => hints,
+            Err(e) if frame.is_main() => {
+                return Err(CommandError::from(e).context("Failed to get page hints"))
             }
-            Err(e) => Err(format!("Script execution failed: {}", e)),
+            Err(e) => {
+                println!("Skipping hints in frame {}: {}", frame.frame_id, e);
+                skipped_frames.push(SkippedFrame::new(&frame.frame_id, &frame.url, e));
+                continue;
+            }
+        };
+        next_hint += hints.elements.len();
+
+        if frame.is_main() {
+            page_scroll = (hints.scroll_x, hints.scroll_y);
+            elements.extend(hints.elements);
+            continue;
+        }
+        if hints.elements.is_empty() {
+            continue;
         }
+
+        let origin = match frame_origin(&client, frame).await {
+            Ok(origin) => origin,
+            Err(e) => {
+                println!("Skipping hints in frame {}: {}", frame.frame_id, e);
+                skipped_frames.push(SkippedFrame::new(&frame.frame_id, &frame.url, e));
+                continue;
+            }
+        };
+        elements.extend(hints.into_page(frame, origin, page_scroll));
+    }
+
+    Ok(PageHints {
+        total_count: elements.len(),
+        visible_count: elements.iter().filter(|element| element.visible).count(),
+        elements,
+        skipped_frames,
     })
 }
 
 #[tauri::command]
-pub fn chrome_clear_page_hints(session_id: String) -> Result<String, String> {
-    run_async(async move {
-        let sessions = get_chrome_sessions();
-        let session = sessions
-            .get(&session_id)
-            .ok_or("Session not found")?
-            .clone();
-
-        let targets = check_chrome_devtools(session.debug_port)
-            .await
-            .map_err(|e| format!("Chrome session is no longer responsive: {}", e))?;
+pub async fn chrome_clear_page_hints(
+    sessions: State<'_, SessionRegistry>,
+    session_id: String,
+    target_id: Option<String>,
+    call_options: Option<CallOptions>,
+) -> Result<String, CommandError> {
+    let session = sessions.get(&session_id)?;
 
-        let target = targets
-            .iter()
-            .find(|t| t.target_type == "page" && !t.url.starts_with("chrome-extension://"))
-            .or_else(|| targets.iter().find(|t| t.target_type == "page"))
-            .or_else(|| targets.first())
-            .ok_or("No suitable target found")?;
-
-        let params = serde_json::json!({
-            "expression": VIMIUM_CLEANUP_SCRIPT,
-            "returnByValue": true
-        });
-
-        match send_cdp_message(&target.websocket_debugger_url, "Runtime.evaluate", params).await {
-            Ok(_) => Ok("Page hints cleared successfully".to_string()),
-            Err(e) => Err(format!("Failed to clear hints: {}", e)),
+    let target = resolve_target(&session, target_id.as_deref()).await?;
+
+    let client = cdp_client(&session, &target)
+        .await?
+        .with_options(call_options);
+
+    for frame in page_frames(&session, &client).await?.frames {
+        match frame
+            .client(&client)
+            .evaluate_in::<serde_json::Value>(frame.context_id, VIMIUM_CLEANUP_SCRIPT)
+            .await
+        {
+            Ok(_) => {}
+            Err(e) if frame.is_main() => {
+                return Err(CommandError::from(e).context("Failed to clear hints"))
+            }
+            Err(e) => println!("Failed to clear hints in frame {}: {}", frame.frame_id, e),
         }
-    })
+    }
+
+    Ok("Page hints cleared successfully".to_string())
 }
 
 #[tauri::command]
-pub fn chrome_interact_with_element(
+pub async fn chrome_interact_with_element(
+    sessions: State<'_, SessionRegistry>,
     session_id: String,
     action: ElementAction,
-) -> Result<String, String> {
-    run_async(async move {
-        let sessions = get_chrome_sessions();
-        let session = sessions
-            .get(&session_id)
-            .ok_or("Session not found")?
-            .clone();
-
-        let targets = check_chrome_devtools(session.debug_port)
+    target_id: Option<String>,
+    call_options: Option<CallOptions>,
+) -> Result<String, CommandError> {
+    let session = sessions.get(&session_id)?;
+
+    let target = resolve_target(&session, target_id.as_deref()).await?;
+
+    let client = cdp_client(&session, &target)
+        .await?
+        .with_options(call_options);
+
+    let frames = page_frames(&session, &client).await?;
+    let frame = match &action.frame_id {
+        Some(frame_id) => frames.find(frame_id)?,
+        // When no frame has the hint, the main frame's script reports it missing as before.
+        // page_frames always lists the main frame first
+        None => find_hint_frame(&client, &frames.frames, &action.hint)
             .await
-            .map_err(|e| format!("Chrome session is no longer responsive: {}", e))?;
+            .unwrap_or(&frames.frames[0]),
+    };
 
-        let target = targets
-            .iter()
-            .find(|t| t.target_type == "page" && !t.url.starts_with("chrome-extension://"))
-            .or_else(|| targets.iter().find(|t| t.target_type == "page"))
-            .or_else(|| targets.first())
-            .ok_or("No suitable target found")?;
-
-        let script = generate_element_action_script(&action);
-        let params = serde_json::json!({
-            "expression": script,
-            "returnByValue": true
-        });
-
-        match send_cdp_message(&target.websocket_debugger_url, "Runtime.evaluate", params).await {
-            Ok(result) => {
-                if let Some(value) = result.get("value") {
-                    Ok(value.to_string())
-                } else if let Some(result_obj) = result.get("result") {
-                    if let Some(value) = result_obj.get("value") {
-                        Ok(value.to_string())
-                    } else {
-                        Ok(result_obj.to_string())
-                    }
-                } else {
-                    Ok(result.to_string())
-                }
-            }
-            Err(e) => Err(format!("Element interaction failed: {}", e)),
+    let result = match action.input_mode.as_deref().unwrap_or("trusted") {
+        "trusted" => perform_trusted_action(&client, frame, &action).await,
+        // Fallback for pages where dispatching input through the browser is unwanted
+        "script" => frame
+            .client(&client)
+            .evaluate_in::<serde_json::Value>(
+                frame.context_id,
+                &generate_element_action_script(&action),
+            )
+            .await
+            .map_err(CommandError::from),
+        mode => return Err(format!("Unknown input mode: {}", mode).into()),
+    };
+
+    match result {
+        Ok(value) => Ok(value.to_string()),
+        Err(e) => Err(e.context("Element interaction failed")),
+    }
+}
+
+impl FrameHints {
+    // Report iframe elements in the same page coordinates as the rest
+    fn into_page(
+        self,
+        frame: &PageFrame,
+        (origin_x, origin_y): (f64, f64),
+        page_scroll: (f64, f64),
+    ) -> impl Iterator<Item = PageElement> + '_ {
+        let (scroll_x, scroll_y) = (self.scroll_x, self.scroll_y);
+        self.elements.into_iter().map(move |mut element| {
+            element.x += origin_x - scroll_x + page_scroll.0;
+            element.y += origin_y - scroll_y + page_scroll.1;
+            element.frame_id = Some(frame.frame_id.clone());
+            element
+        })
+    }
+}
+
+// Frame whose hints include `hint`
+async fn find_hint_frame<'a>(
+    client: &CdpClient,
+    frames: &'a [PageFrame],
+    hint: &str,
+) -> Option<&'a PageFrame> {
+    let script = generate_hint_lookup_script(hint);
+
+    for frame in frames {
+        if let Ok(true) = frame
+            .client(client)
+            .evaluate_read_only::<bool>(frame.context_id, &script)
+            .await
+        {
+            return Some(frame);
         }
-    })
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(hint: &str, x: f64, y: f64) -> PageElement {
+        PageElement {
+            hint: hint.to_string(),
+            tag_name: "a".to_string(),
+            element_type: "link".to_string(),
+            text: String::new(),
+            href: None,
+            x,
+            y,
+            width: 10.0,
+            height: 10.0,
+            visible: true,
+            selector: "a".to_string(),
+            frame_id: None,
+        }
+    }
+
+    #[test]
+    fn hint_script_starts_labels_at_the_offset() {
+        assert!(generate_vimium_script(0).ends_with("(0);"));
+        assert!(generate_vimium_script(27).ends_with("(27);"));
+    }
+
+    #[test]
+    fn iframe_hints_move_into_page_coordinates() {
+        let hints = FrameHints {
+            elements: vec![element("c", 5.0, 300.0), element("d", 20.0, 40.0)],
+            scroll_x: 0.0,
+            scroll_y: 200.0,
+        };
+        let frame = PageFrame {
+            frame_id: "child".to_string(),
+            url: "https://example.com/frame".to_string(),
+            context_id: Some(3),
+            target: None,
+        };
+
+        let placed: Vec<PageElement> = hints
+            .into_page(&frame, (100.0, 50.0), (0.0, 1000.0))
+            .collect();
+
+        assert_eq!(
+            placed
+                .iter()
+                .map(|e| (e.hint.as_str(), e.x, e.y))
+                .collect::<Vec<_>>(),
+            vec![("c", 105.0, 1150.0), ("d", 120.0, 890.0)]
+        );
+        assert!(placed
+            .iter()
+            .all(|e| e.frame_id.as_deref() == Some("child")));
+    }
 }
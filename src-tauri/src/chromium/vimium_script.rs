@@ -1,8 +1,9 @@
 use crate::chromium::vimium::ElementAction;
 
-// JavaScript code to inject for finding and highlighting elements
-pub const VIMIUM_SCRIPT: &str = r#"
-(function() {
+// JavaScript code to inject for finding and highlighting elements, as a function
+// taking the index of the first hint label so labels stay unique across frames
+const VIMIUM_SCRIPT: &str = r#"
+(function(firstHint) {
     // Remove existing hints if any
     const existingHints = document.querySelectorAll('.vimium-hint, .vimium-hint-overlay');
     existingHints.forEach(el => el.remove());
@@ -89,8 +90,8 @@ pub const VIMIUM_SCRIPT: &str = r#"
         });
     });
 
-    // Generate hints for all elements
-    const hints = generateHints(elements.length);
+    // Generate hints for all elements, after the ones earlier frames took
+    const hints = generateHints(firstHint + elements.length).slice(firstHint);
     const pageElements = [];
 
     // Create hint overlays and collect element data
@@ -178,11 +179,25 @@ pub const VIMIUM_SCRIPT: &str = r#"
     return {
         elements: pageElements,
         total_count: elements.length,
-        visible_count: pageElements.filter(el => el.visible).length
+        visible_count: pageElements.filter(el => el.visible).length,
+        scroll_x: window.scrollX,
+        scroll_y: window.scrollY
     };
-})();
+})
 "#;
 
+pub fn generate_vimium_script(first_hint: usize) -> String {
+    format!("{}({});", VIMIUM_SCRIPT, first_hint)
+}
+
+// Whether the hints of the frame the script runs in include `hint`
+pub fn generate_hint_lookup_script(hint: &str) -> String {
+    format!(
+        "(window.vimiumHints || []).includes({})",
+        serde_json::to_string(hint).unwrap_or_default()
+    )
+}
+
 pub const VIMIUM_CLEANUP_SCRIPT: &str = r#"
 (function() {
     // Remove all hint overlays
@@ -381,4 +396,70 @@ pub fn generate_element_action_script(action: &ElementAction) -> String {
 "#,
         action.hint, action.hint, action.hint, action.action_type, escaped_value
     )
-}
\ No newline at end of file
+}
+
+// Scroll the hinted element into view and report where it is now, for input
+// dispatched through CDP. With `focus` the element also gets focus, and with
+// `select` its content is selected so typed text replaces it.
+pub fn generate_element_locate_script(hint: &str, focus: bool, select: bool) -> String {
+    let hint = serde_json::to_string(hint).unwrap_or_default();
+
+    format!(
+        r#"
+(function() {{
+    if (!window.vimiumElements || !window.vimiumHints) {{
+        return {{ success: false, message: "No vimium elements found. Please refresh hints first." }};
+    }}
+
+    const hint = {};
+    const hintIndex = window.vimiumHints.indexOf(hint);
+    if (hintIndex === -1) {{
+        return {{ success: false, message: `Hint '${{hint}}' not found` }};
+    }}
+
+    const element = window.vimiumElements[hintIndex];
+    if (!element || !element.isConnected) {{
+        return {{ success: false, message: `Element not found for hint '${{hint}}'` }};
+    }}
+
+    // Instant scroll, the rect below must be final
+    element.scrollIntoView({{ block: 'center', inline: 'center' }});
+
+    if ({}) {{
+        element.focus();
+    }}
+
+    if ({}) {{
+        if (typeof element.select === 'function') {{
+            element.select();
+        }} else if (element.isContentEditable) {{
+            const range = document.createRange();
+            range.selectNodeContents(element);
+            const selection = window.getSelection();
+            selection.removeAllRanges();
+            selection.addRange(range);
+        }} else {{
+            return {{
+                success: false,
+                message: `Element ${{element.tagName}} does not support text input. Only INPUT, TEXTAREA, and contenteditable elements can be filled.`
+            }};
+        }}
+    }}
+
+    // Same page coordinates as the hints report
+    const rect = element.getBoundingClientRect();
+    return {{
+        success: true,
+        tag_name: element.tagName,
+        x: rect.left + window.scrollX,
+        y: rect.top + window.scrollY,
+        width: rect.width,
+        height: rect.height,
+        scroll_x: window.scrollX,
+        scroll_y: window.scrollY
+    }};
+}})();
+"#,
+        hint, focus, select
+    )
+}
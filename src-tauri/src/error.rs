@@ -0,0 +1,154 @@
+use crate::chromium::cdp::CdpError;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use serde_json::json;
+use std::fmt;
+
+// Error returned by every command. It reaches the frontend as
+// `{ code, message, details }`, where `code` is stable and safe to match on
+// and `message` is for display only.
+#[derive(Debug, Clone)]
+pub enum CommandError {
+    SessionNotFound { session_id: String },
+    // The DevTools HTTP endpoint or a target's WebSocket can't be reached
+    DevToolsUnreachable { port: Option<u16>, reason: String },
+    NoTarget { target_id: Option<String> }, // None when no page was open at all
+    Cdp { code: i64, message: String },
+    ScriptException { message: String },
+    Timeout { operation: String },
+    Cancelled { operation: String },
+    SttFailed { message: String },
+    ParseFailed { input: String, message: String },
+    Failed { message: String },
+    // Any of the above, with what was being attempted when it happened
+    WithContext { context: String, error: Box<CommandError> },
+}
+
+impl CommandError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            CommandError::SessionNotFound { .. } => "session_not_found",
+            CommandError::DevToolsUnreachable { .. } => "devtools_unreachable",
+            CommandError::NoTarget { .. } => "no_target",
+            CommandError::Cdp { .. } => "cdp_error",
+            CommandError::ScriptException { .. } => "script_exception",
+            CommandError::Timeout { .. } => "timeout",
+            CommandError::Cancelled { .. } => "cancelled",
+            CommandError::SttFailed { .. } => "stt_failed",
+            CommandError::ParseFailed { .. } => "parse_failed",
+            CommandError::Failed { .. } => "failed",
+            CommandError::WithContext { error, .. } => error.code(),
+        }
+    }
+
+    fn details(&self) -> serde_json::Value {
+        match self {
+            CommandError::SessionNotFound { session_id } => json!({ "session_id": session_id }),
+            CommandError::DevToolsUnreachable { port, reason } => {
+                json!({ "port": port, "reason": reason })
+            }
+            CommandError::NoTarget { target_id } => json!({ "target_id": target_id }),
+            CommandError::Cdp { code, message } => json!({ "code": code, "message": message }),
+            CommandError::Timeout { operation } | CommandError::Cancelled { operation } => {
+                json!({ "operation": operation })
+            }
+            CommandError::ParseFailed { input, .. } => json!({ "input": input }),
+            CommandError::ScriptException { .. }
+            | CommandError::SttFailed { .. }
+            | CommandError::Failed { .. } => json!({}),
+            CommandError::WithContext { context, error } => {
+                let mut details = error.details();
+                details["context"] = json!(context);
+                details
+            }
+        }
+    }
+
+    // Same error, with what was being attempted in front of the message and in
+    // `details.context`; the code stays that of the underlying error
+    pub fn context(self, context: &str) -> Self {
+        match self {
+            CommandError::WithContext {
+                context: inner,
+                error,
+            } => CommandError::WithContext {
+                context: format!("{}: {}", context, inner),
+                error,
+            },
+            error => CommandError::WithContext {
+                context: context.to_string(),
+                error: Box::new(error),
+            },
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::SessionNotFound { session_id } => write!(f, "Session {} not found", session_id),
+            CommandError::DevToolsUnreachable { port: Some(port), reason } => {
+                write!(f, "Chrome DevTools on port {} is unreachable: {}", port, reason)
+            }
+            CommandError::DevToolsUnreachable { port: None, reason } => {
+                write!(f, "Chrome DevTools is unreachable: {}", reason)
+            }
+            CommandError::NoTarget { target_id: Some(target_id) } => write!(f, "Target {} not found", target_id),
+            CommandError::NoTarget { target_id: None } => write!(f, "No suitable target found"),
+            CommandError::Cdp { code, message } => write!(f, "CDP Error {}: {}", code, message),
+            CommandError::ScriptException { message } => write!(f, "Script exception: {}", message),
+            CommandError::Timeout { operation } => write!(f, "Timed out: {}", operation),
+            CommandError::Cancelled { operation } => write!(f, "Cancelled: {}", operation),
+            CommandError::SttFailed { message } => write!(f, "Speech-to-text failed: {}", message),
+            CommandError::ParseFailed { message, .. } => write!(f, "{}", message),
+            CommandError::Failed { message } => write!(f, "{}", message),
+            CommandError::WithContext { context, error } => write!(f, "{}: {}", context, error),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl Serialize for CommandError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("CommandError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
+    }
+}
+
+impl From<CdpError> for CommandError {
+    fn from(error: CdpError) -> Self {
+        match error {
+            CdpError::Connection(reason) => CommandError::DevToolsUnreachable { port: None, reason },
+            CdpError::Timeout(method) => CommandError::Timeout { operation: method },
+            CdpError::Protocol { code, message } => CommandError::Cdp { code, message },
+            CdpError::Exception(message) => CommandError::ScriptException { message },
+            CdpError::Serialization(message) => CommandError::Failed { message },
+            CdpError::Cancelled(method) => CommandError::Cancelled { operation: method },
+        }
+    }
+}
+
+// Helpers that still report plain messages
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        CommandError::Failed { message }
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        CommandError::Failed {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<CommandError> for String {
+    fn from(error: CommandError) -> Self {
+        error.to_string()
+    }
+}
@@ -1,17 +1,50 @@
 mod chromium;
+mod error;
+mod settings;
 mod voice;
 
+use crate::chromium::browsers::chrome_list_browsers;
+use crate::chromium::capture::{chrome_capture_screenshot, chrome_save_mhtml, chrome_save_pdf};
+use crate::chromium::console::{
+    chrome_clear_console_logs, chrome_get_console_logs, chrome_stream_console_logs,
+};
+use crate::chromium::events::{chrome_subscribe_events, chrome_unsubscribe_events};
+use crate::chromium::har::{chrome_start_har_recording, chrome_stop_har_recording};
+use crate::chromium::interception::{
+    chrome_add_network_rule, chrome_list_network_rules, chrome_load_network_rules,
+    chrome_remove_network_rule,
+};
 use crate::chromium::lib::{
     chrome_debug_info, chrome_execute_script, chrome_get_targets, open_chrome_with_control,
-    ChromeSession,
+};
+use crate::chromium::navigation::{
+    chrome_go_back, chrome_go_forward, chrome_navigate, chrome_reload,
+};
+use crate::chromium::presets::{
+    chrome_delete_launch_preset, chrome_list_launch_presets, chrome_save_launch_preset,
+};
+use crate::chromium::process::{
+    chrome_close_session, chrome_session_status, shutdown_chrome_sessions,
+};
+use crate::chromium::profiles::chrome_get_profiles;
+use crate::chromium::sessions::{
+    chrome_cancel, chrome_list_sessions, chrome_rename_session, restore_sessions,
+    SessionRegistry,
+};
+use crate::chromium::startup::chrome_get_startup_log;
+use crate::chromium::storage::{chrome_export_storage, chrome_import_storage};
+use crate::chromium::tabs::{
+    chrome_activate_tab, chrome_close_tab, chrome_get_active_target, chrome_list_tabs,
+    chrome_new_tab, chrome_reload_tab,
 };
 use crate::chromium::vimium::{
     chrome_clear_page_hints, chrome_interact_with_element, chrome_show_page_hints,
 };
-use futures_util::StreamExt;
+use crate::error::CommandError;
+use crate::settings::{get_settings, load_settings, save_settings};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 use std::process::Command;
+use tauri::Manager;
 use voice::{transcribe_audio, execute_voice_command};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -26,27 +59,6 @@ pub struct ParsedCommand {
     target: String,
 }
 
-// Global state to track Chrome sessions
-static mut CHROME_SESSIONS: Option<HashMap<String, ChromeSession>> = None;
-static mut MESSAGE_ID_COUNTER: u32 = 1;
-
-fn get_chrome_sessions() -> &'static mut HashMap<String, ChromeSession> {
-    unsafe {
-        if CHROME_SESSIONS.is_none() {
-            CHROME_SESSIONS = Some(HashMap::new());
-        }
-        CHROME_SESSIONS.as_mut().unwrap()
-    }
-}
-
-fn get_next_message_id() -> u32 {
-    unsafe {
-        let id = MESSAGE_ID_COUNTER;
-        MESSAGE_ID_COUNTER += 1;
-        id
-    }
-}
-
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -54,13 +66,14 @@ fn greet(name: &str) -> String {
 }
 
 #[tauri::command]
-fn parse_command(input: &str) -> Result<ParsedCommand, String> {
-    let parts: Vec<&str> = input.trim().split_whitespace().collect();
+fn parse_command(input: &str) -> Result<ParsedCommand, CommandError> {
+    let parts: Vec<&str> = input.split_whitespace().collect();
 
     if parts.len() < 2 {
-        return Err(
-            "Command must have at least action and target (e.g., 'Open Chrome')".to_string(),
-        );
+        return Err(CommandError::ParseFailed {
+            input: input.to_string(),
+            message: "Command must have at least action and target (e.g., 'Open Chrome')".to_string(),
+        });
     }
 
     let action = parts[0].to_lowercase();
@@ -80,28 +93,6 @@ fn execute_os_command(parsed_command: ParsedCommand) -> CommandResponse {
     }
 }
 
-#[tauri::command]
-fn chrome_get_profiles() -> Result<Vec<String>, String> {
-    // This is a simplified implementation
-    // In a real app, you'd scan the Chrome user data directory
-    let common_profiles = vec![
-        "Default".to_string(),
-        "Profile 1".to_string(),
-        "Profile 2".to_string(),
-        "Profile 3".to_string(),
-    ];
-
-    Ok(common_profiles)
-}
-
-// Helper function to run async code in Tauri commands
-fn run_async<F, T>(future: F) -> T
-where
-    F: std::future::Future<Output = T>,
-{
-    tokio::runtime::Runtime::new().unwrap().block_on(future)
-}
-
 fn open_application(app_name: &str) -> CommandResponse {
     let result = match app_name {
         "chrome" | "google chrome" => {
@@ -183,7 +174,7 @@ fn open_application(app_name: &str) -> CommandResponse {
 }
 
 #[tauri::command]
-fn open_file_dialog() -> Result<String, String> {
+fn open_file_dialog() -> Result<String, CommandError> {
     // This will be enhanced later for file/folder operations
     Ok("File dialog functionality coming soon".to_string())
 }
@@ -223,23 +214,77 @@ fn open_folder(path: &str) -> CommandResponse {
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .setup(|app| {
+            app.manage(SessionRegistry::new(app.handle()));
+            restore_sessions(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             greet,
             parse_command,
             execute_os_command,
             open_file_dialog,
             open_folder,
+            get_settings,
+            save_settings,
             open_chrome_with_control,
             chrome_get_profiles,
+            chrome_list_browsers,
+            chrome_list_launch_presets,
+            chrome_save_launch_preset,
+            chrome_delete_launch_preset,
             chrome_execute_script,
             chrome_debug_info,
+            chrome_list_sessions,
+            chrome_rename_session,
+            chrome_close_session,
+            chrome_cancel,
+            chrome_session_status,
+            chrome_get_startup_log,
             chrome_get_targets,
+            chrome_get_active_target,
+            chrome_list_tabs,
+            chrome_new_tab,
+            chrome_close_tab,
+            chrome_activate_tab,
+            chrome_reload_tab,
+            chrome_navigate,
+            chrome_go_back,
+            chrome_go_forward,
+            chrome_reload,
+            chrome_capture_screenshot,
+            chrome_save_pdf,
+            chrome_save_mhtml,
+            chrome_add_network_rule,
+            chrome_remove_network_rule,
+            chrome_list_network_rules,
+            chrome_load_network_rules,
+            chrome_start_har_recording,
+            chrome_stop_har_recording,
+            chrome_export_storage,
+            chrome_import_storage,
+            chrome_get_console_logs,
+            chrome_clear_console_logs,
+            chrome_stream_console_logs,
             chrome_show_page_hints,
             chrome_clear_page_hints,
             chrome_interact_with_element,
+            chrome_subscribe_events,
+            chrome_unsubscribe_events,
             transcribe_audio,        // Add this line
             execute_voice_command    // Add this line
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            // Launched browsers keep running and are reattached on the next start,
+            // unless the settings ask for them to close with the app
+            if let tauri::RunEvent::Exit = event {
+                if load_settings(app).close_browsers_on_exit {
+                    tauri::async_runtime::block_on(shutdown_chrome_sessions(
+                        &app.state::<SessionRegistry>(),
+                    ));
+                }
+            }
+        });
 }
\ No newline at end of file
@@ -0,0 +1,109 @@
+use crate::chromium::cdp::CallPolicy;
+use crate::chromium::sessions::SessionRegistry;
+use crate::error::CommandError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+// File in the app's config dir holding the settings
+const SETTINGS_FILE: &str = "settings.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AppSettings {
+    pub cdp_timeout_ms: u64, // How long a CDP request waits for its response
+    pub cdp_retries: u32, // Extra attempts of read-only calls after a timeout or a page navigating mid-call
+    pub cdp_retry_delay_ms: u64,
+    pub close_browsers_on_exit: bool, // Kill the browsers the app launched when it quits
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            cdp_timeout_ms: 10_000,
+            cdp_retries: 2,
+            cdp_retry_delay_ms: 500,
+            close_browsers_on_exit: false,
+        }
+    }
+}
+
+impl AppSettings {
+    pub fn call_policy(&self) -> CallPolicy {
+        CallPolicy {
+            timeout: Duration::from_millis(self.cdp_timeout_ms),
+            retries: self.cdp_retries,
+            retry_delay: Duration::from_millis(self.cdp_retry_delay_ms),
+        }
+    }
+}
+
+// Saved settings, or the defaults when there are none or they can't be read
+pub fn load_settings(app: &AppHandle) -> AppSettings {
+    match read_settings(app) {
+        Ok(settings) => settings,
+        Err(e) => {
+            println!("Using default settings: {}", e);
+            AppSettings::default()
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_settings(app: AppHandle) -> Result<AppSettings, CommandError> {
+    read_settings(&app)
+}
+
+#[tauri::command]
+pub fn save_settings(
+    app: AppHandle,
+    sessions: State<'_, SessionRegistry>,
+    settings: AppSettings,
+) -> Result<AppSettings, CommandError> {
+    if settings.cdp_timeout_ms == 0 {
+        return Err("CDP timeout must be greater than zero".into());
+    }
+
+    write_settings(&app, &settings)?;
+
+    // Open sessions pick up the new defaults right away
+    sessions.set_call_policy(settings.call_policy());
+
+    println!("Saved settings: {:?}", settings);
+    Ok(settings)
+}
+
+fn settings_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(SETTINGS_FILE))
+        .map_err(|e| format!("Failed to locate app config dir: {}", e))
+}
+
+fn read_settings(app: &AppHandle) -> Result<AppSettings, CommandError> {
+    let path = settings_path(app)?;
+    if !path.exists() {
+        return Ok(AppSettings::default());
+    }
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&contents).map_err(|e| CommandError::ParseFailed {
+        input: path.to_string_lossy().to_string(),
+        message: format!("Failed to parse {}: {}", path.display(), e),
+    })
+}
+
+fn write_settings(app: &AppHandle, settings: &AppSettings) -> Result<(), CommandError> {
+    let path = settings_path(app)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+
+    let contents = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    std::fs::write(&path, contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e).into())
+}
@@ -1,7 +1,15 @@
+use crate::chromium::capture::{chrome_save_mhtml, chrome_save_pdf, default_export_path, PdfOptions};
 use crate::chromium::lib::chrome_execute_script;
+use crate::chromium::navigation::{
+    chrome_go_back, chrome_go_forward, chrome_navigate, chrome_reload, NavigationResult,
+};
+use crate::chromium::sessions::SessionRegistry;
+use crate::chromium::tabs::{chrome_activate_tab, chrome_close_tab, chrome_list_tabs, chrome_new_tab};
 use crate::chromium::vimium::{chrome_clear_page_hints, chrome_interact_with_element, chrome_show_page_hints};
-use crate::{execute_os_command, parse_command, run_async, CommandResponse};
+use crate::error::CommandError;
+use crate::{execute_os_command, parse_command, CommandResponse};
 use serde::{Deserialize, Serialize};
+use tauri::State;
 
 // Voice control structures
 #[derive(Debug, Serialize, Deserialize)]
@@ -26,9 +34,11 @@ pub async fn transcribe_audio(
     audio_data: Vec<u8>,
     voice_mode: String,
     _chrome_session_id: Option<String>, // Prefixed with _ to avoid unused warning
-) -> Result<String, String> {
+) -> Result<String, CommandError> {
     if audio_data.is_empty() {
-        return Err("No audio data provided".to_string());
+        return Err(CommandError::SttFailed {
+            message: "No audio data provided".to_string(),
+        });
     }
 
     println!("Transcribing audio: {} bytes in {} mode", audio_data.len(), voice_mode);
@@ -48,7 +58,7 @@ pub async fn transcribe_audio(
         }
         Err(e) => {
             println!("Transcription failed: {}", e);
-            Err(format!("Speech-to-text failed: {}", e))
+            Err(CommandError::SttFailed { message: e })
         }
     }
 }
@@ -89,22 +99,23 @@ async fn send_to_stt_service(request: STTRequest) -> Result<String, String> {
 
 #[tauri::command]
 pub async fn execute_voice_command(
+    sessions: State<'_, SessionRegistry>,
     command: String,
     voice_mode: String,
     chrome_session_id: Option<String>,
-) -> Result<CommandResponse, String> {
+) -> Result<CommandResponse, CommandError> {
     println!("Executing voice command: '{}' in mode: {}", command, voice_mode);
 
     let command_lower = command.to_lowercase();
 
     match voice_mode.as_str() {
-        "chrome" => execute_chrome_voice_command(command_lower, chrome_session_id).await,
-        "vimium" => execute_vimium_voice_command(command_lower, chrome_session_id).await,
+        "chrome" => execute_chrome_voice_command(sessions, command_lower, chrome_session_id).await,
+        "vimium" => execute_vimium_voice_command(sessions, command_lower, chrome_session_id).await,
         _ => execute_general_voice_command(command).await,
     }
 }
 
-async fn execute_general_voice_command(command: String) -> Result<CommandResponse, String> {
+async fn execute_general_voice_command(command: String) -> Result<CommandResponse, CommandError> {
     // Try to parse as a regular command first
     match parse_command(command.as_str()) {
         Ok(parsed) => {
@@ -131,107 +142,168 @@ async fn execute_general_voice_command(command: String) -> Result<CommandRespons
                     message: "Voice mode switched to General".to_string(),
                 })
             } else {
-                Err(format!("Unrecognized voice command: '{}'", command))
+                Err(unparsed(&command, &format!("Unrecognized voice command: '{}'", command)))
             }
         }
     }
 }
 
 async fn execute_chrome_voice_command(
+    sessions: State<'_, SessionRegistry>,
     command: String,
     chrome_session_id: Option<String>,
-) -> Result<CommandResponse, String> {
+) -> Result<CommandResponse, CommandError> {
     let session_id = chrome_session_id.ok_or("No Chrome session available for Chrome voice commands")?;
 
-    if command.contains("navigate to") || command.contains("go to") {
+    if command.contains("new tab") {
+        // "new tab", "open new tab github.com", "new tab with example.com"
+        let url = command
+            .split("new tab")
+            .nth(1)
+            .map(|rest| {
+                let rest = rest.trim();
+                ["with ", "to ", "at "]
+                    .iter()
+                    .find_map(|prefix| rest.strip_prefix(prefix))
+                    .unwrap_or(rest)
+                    .trim()
+            })
+            .filter(|rest| !rest.is_empty())
+            .map(normalize_url);
+
+        match chrome_new_tab(sessions.clone(), session_id, url.clone()).await {
+            Ok(_) => Ok(CommandResponse {
+                success: true,
+                message: match &url {
+                    Some(url) => format!("Opened {} in a new tab", url),
+                    None => "Opened new tab".to_string(),
+                },
+            }),
+            Err(e) => Err(e.context("New tab command failed")),
+        }
+    } else if command.contains("close tab") {
+        match chrome_close_tab(sessions.clone(), session_id, None).await {
+            Ok(message) => Ok(CommandResponse {
+                success: true,
+                message,
+            }),
+            Err(e) => Err(e.context("Close tab command failed")),
+        }
+    } else if command.contains("switch to tab") || command.contains("go to tab") {
+        let tab_number = extract_tab_number_from_command(&command)
+            .ok_or_else(|| unparsed(&command, "Could not identify tab number. Try saying 'switch to tab 3'"))?;
+
+        let tabs = chrome_list_tabs(sessions.clone(), session_id.clone())
+            .await
+            .map_err(|e| e.context("Switch tab command failed"))?;
+        let tab = tabs
+            .get(tab_number - 1)
+            .ok_or(format!("There is no tab {}, only {} open", tab_number, tabs.len()))?;
+
+        match chrome_activate_tab(sessions.clone(), session_id, tab.id.clone()).await {
+            Ok(target) => Ok(CommandResponse {
+                success: true,
+                message: format!("Switched to tab {}: {}", tab_number, target.title),
+            }),
+            Err(e) => Err(e.context("Switch tab command failed")),
+        }
+    } else if command.contains("save page as pdf") || command.contains("save as pdf") {
+        let options = PdfOptions {
+            print_background: Some(true),
+            path: Some(default_export_path("pdf")),
+            ..Default::default()
+        };
+
+        match chrome_save_pdf(sessions.clone(), session_id, options, None, None).await {
+            Ok(result) => Ok(CommandResponse {
+                success: true,
+                message: format!("Saved page as PDF to {}", result.path.unwrap_or_default()),
+            }),
+            Err(e) => Err(e.context("Save as PDF failed")),
+        }
+    } else if command.contains("save page as mhtml") || command.contains("save page archive") {
+        let path = default_export_path("mhtml");
+
+        match chrome_save_mhtml(sessions.clone(), session_id, Some(path), None, None).await {
+            Ok(result) => Ok(CommandResponse {
+                success: true,
+                message: format!("Saved page archive to {}", result.path.unwrap_or_default()),
+            }),
+            Err(e) => Err(e.context("Save page archive failed")),
+        }
+    } else if command.contains("navigate to") || command.contains("go to") {
         // Extract URL from command
         let url = if let Some(url_start) = command.find("to ") {
-            let url_part = &command[url_start + 3..].trim();
-            // Add https:// if no protocol specified
-            if url_part.starts_with("http://") || url_part.starts_with("https://") {
-                url_part.to_string()
-            } else {
-                format!("https://{}", url_part)
-            }
+            normalize_url(command[url_start + 3..].trim())
         } else {
-            return Err("Could not extract URL from navigation command".to_string());
+            return Err(unparsed(&command, "Could not extract URL from navigation command"));
         };
 
-        let script = format!("window.location.href = '{}'", url);
-
-        run_async(async {
-            match chrome_execute_script(session_id, script) {
-                Ok(_) => Ok(CommandResponse {
-                    success: true,
-                    message: format!("Navigated to {}", url),
-                }),
-                Err(e) => Err(format!("Navigation failed: {}", e)),
-            }
-        })
+        match chrome_navigate(sessions.clone(), session_id, url.clone(), None, None, None).await {
+            Ok(result) => Ok(navigation_response(result, &format!("Navigated to {}", url))),
+            Err(e) => Err(e.context("Navigation failed")),
+        }
+    } else if command.contains("go back") {
+        match chrome_go_back(sessions.clone(), session_id, None, None, None).await {
+            Ok(result) => Ok(navigation_response(result, "Went back")),
+            Err(e) => Err(e.context("Go back failed")),
+        }
+    } else if command.contains("go forward") {
+        match chrome_go_forward(sessions.clone(), session_id, None, None, None).await {
+            Ok(result) => Ok(navigation_response(result, "Went forward")),
+            Err(e) => Err(e.context("Go forward failed")),
+        }
     } else if command.contains("scroll down") {
         let script = "window.scrollBy(0, 500)".to_string();
-        run_async(async {
-            match chrome_execute_script(session_id, script) {
-                Ok(_) => Ok(CommandResponse {
-                    success: true,
-                    message: "Scrolled down".to_string(),
-                }),
-                Err(e) => Err(format!("Scroll command failed: {}", e)),
-            }
-        })
+        match chrome_execute_script(sessions.clone(), session_id, script, None, None).await {
+            Ok(_) => Ok(CommandResponse {
+                success: true,
+                message: "Scrolled down".to_string(),
+            }),
+            Err(e) => Err(e.context("Scroll command failed")),
+        }
     } else if command.contains("scroll up") {
         let script = "window.scrollBy(0, -500)".to_string();
-        run_async(async {
-            match chrome_execute_script(session_id, script) {
-                Ok(_) => Ok(CommandResponse {
-                    success: true,
-                    message: "Scrolled up".to_string(),
-                }),
-                Err(e) => Err(format!("Scroll command failed: {}", e)),
-            }
-        })
+        match chrome_execute_script(sessions.clone(), session_id, script, None, None).await {
+            Ok(_) => Ok(CommandResponse {
+                success: true,
+                message: "Scrolled up".to_string(),
+            }),
+            Err(e) => Err(e.context("Scroll command failed")),
+        }
     } else if command.contains("refresh") || command.contains("reload") {
-        let script = "window.location.reload()".to_string();
-        run_async(async {
-            match chrome_execute_script(session_id, script) {
-                Ok(_) => Ok(CommandResponse {
-                    success: true,
-                    message: "Page refreshed".to_string(),
-                }),
-                Err(e) => Err(format!("Refresh command failed: {}", e)),
-            }
-        })
+        match chrome_reload(sessions.clone(), session_id, None, None, None, None).await {
+            Ok(result) => Ok(navigation_response(result, "Page refreshed")),
+            Err(e) => Err(e.context("Refresh command failed")),
+        }
     } else {
-        Err(format!("Unrecognized Chrome voice command: '{}'", command))
+        Err(unparsed(&command, &format!("Unrecognized Chrome voice command: '{}'", command)))
     }
 }
 
 async fn execute_vimium_voice_command(
+    sessions: State<'_, SessionRegistry>,
     command: String,
     chrome_session_id: Option<String>,
-) -> Result<CommandResponse, String> {
+) -> Result<CommandResponse, CommandError> {
     let session_id = chrome_session_id.ok_or("No Chrome session available for Vimium voice commands")?;
 
     if command.contains("show hints") || command.contains("show page hints") {
-        run_async(async {
-            match chrome_show_page_hints(session_id) {
-                Ok(_) => Ok(CommandResponse {
-                    success: true,
-                    message: "Page hints displayed".to_string(),
-                }),
-                Err(e) => Err(format!("Show hints failed: {}", e)),
-            }
-        })
+        match chrome_show_page_hints(sessions.clone(), session_id, None, None).await {
+            Ok(_) => Ok(CommandResponse {
+                success: true,
+                message: "Page hints displayed".to_string(),
+            }),
+            Err(e) => Err(e.context("Show hints failed")),
+        }
     } else if command.contains("clear hints") || command.contains("hide hints") {
-        run_async(async {
-            match chrome_clear_page_hints(session_id) {
-                Ok(_) => Ok(CommandResponse {
-                    success: true,
-                    message: "Page hints cleared".to_string(),
-                }),
-                Err(e) => Err(format!("Clear hints failed: {}", e)),
-            }
-        })
+        match chrome_clear_page_hints(sessions.clone(), session_id, None, None).await {
+            Ok(_) => Ok(CommandResponse {
+                success: true,
+                message: "Page hints cleared".to_string(),
+            }),
+            Err(e) => Err(e.context("Clear hints failed")),
+        }
     } else if command.contains("click") || command.contains("select") {
         // Extract hint letter from voice command
         if let Some(hint) = extract_hint_from_command(&command) {
@@ -240,19 +312,19 @@ async fn execute_vimium_voice_command(
                 action_type: "click".to_string(),
                 modifier_keys: None,
                 value: None,
+                input_mode: None,
+                frame_id: None,
             };
 
-            run_async(async {
-                match chrome_interact_with_element(session_id, action) {
-                    Ok(_) => Ok(CommandResponse {
-                        success: true,
-                        message: format!("Clicked element {}", hint.to_uppercase()),
-                    }),
-                    Err(e) => Err(format!("Click command failed: {}", e)),
-                }
-            })
+            match chrome_interact_with_element(sessions.clone(), session_id, action, None, None).await {
+                Ok(_) => Ok(CommandResponse {
+                    success: true,
+                    message: format!("Clicked element {}", hint.to_uppercase()),
+                }),
+                Err(e) => Err(e.context("Click command failed")),
+            }
         } else {
-            Err("Could not identify element hint in voice command. Try saying 'click A' or 'select B'".to_string())
+            Err(unparsed(&command, "Could not identify element hint in voice command. Try saying 'click A' or 'select B'"))
         }
     } else if command.contains("fill") || command.contains("type") {
         // Extract hint and text to fill
@@ -263,25 +335,70 @@ async fn execute_vimium_voice_command(
                 action_type: "fill".to_string(),
                 modifier_keys: None,
                 value: Some(text),
+                input_mode: None,
+                frame_id: None,
             };
 
-            run_async(async {
-                match chrome_interact_with_element(session_id, action) {
-                    Ok(_) => Ok(CommandResponse {
-                        success: true,
-                        message: format!("Filled element {} with '{}'", hint.to_uppercase(), text_clone),
-                    }),
-                    Err(e) => Err(format!("Fill command failed: {}", e)),
-                }
-            })
+            match chrome_interact_with_element(sessions.clone(), session_id, action, None, None).await {
+                Ok(_) => Ok(CommandResponse {
+                    success: true,
+                    message: format!("Filled element {} with '{}'", hint.to_uppercase(), text_clone),
+                }),
+                Err(e) => Err(e.context("Fill command failed")),
+            }
         } else {
-            Err("Could not parse fill command. Try saying 'fill A with hello world'".to_string())
+            Err(unparsed(&command, "Could not parse fill command. Try saying 'fill A with hello world'"))
         }
     } else {
-        Err(format!("Unrecognized Vimium voice command: '{}'", command))
+        Err(unparsed(&command, &format!("Unrecognized Vimium voice command: '{}'", command)))
     }
 }
 
+// A transcript that didn't match any voice command
+fn unparsed(command: &str, message: &str) -> CommandError {
+    CommandError::ParseFailed {
+        input: command.to_string(),
+        message: message.to_string(),
+    }
+}
+
+// The page may have failed to load even though navigating worked
+fn navigation_response(result: NavigationResult, message: &str) -> CommandResponse {
+    match result.error {
+        Some(error) => CommandResponse {
+            success: false,
+            message: format!("Failed to load {}: {}", result.url, error),
+        },
+        None => CommandResponse {
+            success: true,
+            message: format!("{} ({})", message, result.url),
+        },
+    }
+}
+
+// Add https:// if no protocol specified
+fn normalize_url(url_part: &str) -> String {
+    if url_part.starts_with("http://") || url_part.starts_with("https://") {
+        url_part.to_string()
+    } else {
+        format!("https://{}", url_part)
+    }
+}
+
+// Helper function to extract a 1-based tab number, spoken as digits or words
+fn extract_tab_number_from_command(command: &str) -> Option<usize> {
+    const NUMBER_WORDS: [&str; 10] = [
+        "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    ];
+
+    command.split_whitespace().find_map(|word| {
+        word.parse::<usize>()
+            .ok()
+            .or_else(|| NUMBER_WORDS.iter().position(|w| *w == word).map(|i| i + 1))
+            .filter(|number| *number > 0)
+    })
+}
+
 // Helper function to extract hint letter from voice command
 fn extract_hint_from_command(command: &str) -> Option<char> {
     // Look for single letters in the command
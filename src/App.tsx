@@ -1,7 +1,7 @@
 import {useState} from "react";
 import {invoke} from "@tauri-apps/api/core";
 import "./App.css";
-import {ChromeSession, CommandResponse, ParsedCommand} from "./types/Control.tsx";
+import {ChromeSession, CommandResponse, ParsedCommand, errorMessage} from "./types/Control.tsx";
 import PresetCommands from "./components/PresetCommands.tsx";
 import ChromeControl from "./components/ChromeControl.tsx";
 import VoiceControl from "./components/VoiceControl.tsx";
@@ -38,7 +38,7 @@ function App() {
         } catch (error) {
             setResult({
                 success: false,
-                message: `Error: ${error}`
+                message: `Error: ${errorMessage(error)}`
             });
         } finally {
             setIsProcessing(false);
@@ -53,7 +53,7 @@ function App() {
         } catch (error) {
             setResult({
                 success: false,
-                message: `Error opening folder: ${error}`
+                message: `Error opening folder: ${errorMessage(error)}`
             });
         } finally {
             setIsProcessing(false);
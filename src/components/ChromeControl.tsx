@@ -1,5 +1,5 @@
 import {useEffect, useState} from "react";
-import {ChromeControlOptions, ChromeSession, ChromeTarget, CommandResponse} from "../types/Control.tsx";
+import {ChromeControlOptions, ChromeProfile, ChromeSession, ChromeTarget, CommandResponse, errorMessage} from "../types/Control.tsx";
 import {invoke} from "@tauri-apps/api/core";
 import ChromeTargets from "./ChromeTargets.tsx";
 import VimiumShow from "./VimiumShow.tsx";
@@ -11,10 +11,13 @@ interface ChromeControlProps {
     setChromeSession: (session: ChromeSession | null) => void; // Add this line
 }
 
+// Profile directories repeat across browsers, so key them by user-data dir too
+const profileKey = (profile: ChromeProfile) => `${profile.user_data_dir}/${profile.directory}`;
+
 export default function ChromeControl({isProcessing, setIsProcessing, setResult, setChromeSession}: ChromeControlProps) {
     const [chromeSession, setChromeSessionLocal] = useState<ChromeSession | null>(null);
-    const [profiles, setProfiles] = useState<string[]>([]);
-    const [selectedProfile, setSelectedProfile] = useState<string>("Default");
+    const [profiles, setProfiles] = useState<ChromeProfile[]>([]);
+    const [selectedProfile, setSelectedProfile] = useState<string>("");
     const [navigationUrl, setNavigationUrl] = useState<string>("https://www.google.com");
     const [scriptToExecute, setScriptToExecute] = useState<string>("document.title");
     const [scriptResult, setScriptResult] = useState<string>("");
@@ -34,7 +37,7 @@ export default function ChromeControl({isProcessing, setIsProcessing, setResult,
 
     const loadChromeProfiles = async () => {
         try {
-            const profileList: string[] = await invoke("chrome_get_profiles");
+            const profileList: ChromeProfile[] = await invoke("chrome_get_profiles");
             setProfiles(profileList);
         } catch (error) {
             console.error("Failed to load Chrome profiles:", error);
@@ -46,8 +49,10 @@ export default function ChromeControl({isProcessing, setIsProcessing, setResult,
         setDebugInfo("");
         setChromeTargets([]);
         try {
+            const profile = profiles.find((p) => profileKey(p) === selectedProfile);
             const options: ChromeControlOptions = {
-                profile: selectedProfile !== "Default" ? selectedProfile : undefined,
+                profile: profile?.directory,
+                user_data_dir: profile?.user_data_dir,
                 url: navigationUrl,
                 debug_port: 9222
             };
@@ -56,7 +61,7 @@ export default function ChromeControl({isProcessing, setIsProcessing, setResult,
             updateChromeSession(session);
             setResult({
                 success: true,
-                message: `Chrome opened with control enabled. Session ID: ${session.session_id}, Port: ${session.debug_port}`
+                message: `${session.browser ?? "Chrome"} opened with control enabled. Session ID: ${session.session_id}, Port: ${session.debug_port}`
             });
 
             setTimeout(async () => {
@@ -65,7 +70,7 @@ export default function ChromeControl({isProcessing, setIsProcessing, setResult,
         } catch (error) {
             setResult({
                 success: false,
-                message: `Failed to open Chrome with control: ${error}`
+                message: `Failed to open Chrome with control: ${errorMessage(error)}`
             });
         } finally {
             setIsProcessing(false);
@@ -95,7 +100,7 @@ export default function ChromeControl({isProcessing, setIsProcessing, setResult,
         } catch (error) {
             setResult({
                 success: false,
-                message: `Script execution failed: ${error}`
+                message: `Script execution failed: ${errorMessage(error)}`
             });
             setScriptResult("");
         } finally {
@@ -117,7 +122,7 @@ export default function ChromeControl({isProcessing, setIsProcessing, setResult,
             setDebugInfo(info);
             await loadChromeTargets();
         } catch (error) {
-            setDebugInfo(`Debug info failed: ${error}`);
+            setDebugInfo(`Debug info failed: ${errorMessage(error)}`);
         } finally {
             setIsProcessing(false);
         }
@@ -147,6 +152,18 @@ export default function ChromeControl({isProcessing, setIsProcessing, setResult,
         }
     };
 
+    const cancelOperations = async () => {
+        if (!chromeSession) {
+            return;
+        }
+
+        try {
+            await invoke("chrome_cancel", {sessionId: chromeSession.session_id});
+        } catch (error) {
+            console.error("Failed to cancel Chrome operations:", error);
+        }
+    };
+
     const closeChromeSession = () => {
         updateChromeSession(null);
         setChromeTargets([]);
@@ -184,9 +201,10 @@ export default function ChromeControl({isProcessing, setIsProcessing, setResult,
                                 className="input-primary"
                                 disabled={isProcessing}
                             >
+                                <option value="">Default</option>
                                 {profiles.map((profile) => (
-                                    <option key={profile} value={profile}>
-                                        {profile}
+                                    <option key={profileKey(profile)} value={profileKey(profile)}>
+                                        {profile.name} ({profile.browser})
                                     </option>
                                 ))}
                             </select>
@@ -287,6 +305,14 @@ export default function ChromeControl({isProcessing, setIsProcessing, setResult,
                                 >
                                     Execute Script
                                 </button>
+                                {isProcessing && chromeSession && (
+                                    <button
+                                        onClick={cancelOperations}
+                                        className="btn-secondary w-full"
+                                    >
+                                        Cancel
+                                    </button>
+                                )}
                                 {scriptResult && (
                                     <div className="p-3 bg-gray-100 dark:bg-gray-700 rounded-lg">
                                         <p className="text-sm text-gray-600 dark:text-gray-400 mb-1">Result:</p>
@@ -1,6 +1,6 @@
 import {useEffect, useRef, useState} from "react";
 import {invoke} from "@tauri-apps/api/core";
-import {ChromeSession, CommandResponse, ElementAction, PageElement} from "../types/Control.tsx";
+import {ChromeSession, CommandResponse, ElementAction, PageElement, errorMessage} from "../types/Control.tsx";
 
 interface HintSelectionProps {
     chromeSession: ChromeSession;
@@ -31,6 +31,7 @@ export default function HintSelection({
                                       }: HintSelectionProps) {
     const [selectedActionType, setSelectedActionType] = useState<string>("click");
     const [fillValue, setFillValue] = useState<string>("");
+    const [inputMode, setInputMode] = useState<string>("trusted");
 
     const hintInputRef = useRef<HTMLInputElement>(null);
     const fillInputRef = useRef<HTMLInputElement>(null);
@@ -68,7 +69,9 @@ export default function HintSelection({
             const action: ElementAction = {
                 hint: hint,
                 action_type: actionType,
-                value: (actionType === "fill" || actionType === "set_value") ? (value || fillValue) : undefined
+                value: (actionType === "fill" || actionType === "set_value") ? (value || fillValue) : undefined,
+                input_mode: inputMode,
+                frame_id: getFilteredElements().find(el => el.hint === hint)?.frame_id
             };
 
             const result: string = await invoke("chrome_interact_with_element", {
@@ -94,7 +97,7 @@ export default function HintSelection({
         } catch (error) {
             setResult({
                 success: false,
-                message: `Failed to interact with element: ${error}`
+                message: `Failed to interact with element: ${errorMessage(error)}`
             });
         } finally {
             setIsProcessing(false);
@@ -218,6 +221,21 @@ export default function HintSelection({
                         </select>
                     </div>
 
+                    <div>
+                        <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                            Input Mode
+                        </label>
+                        <select
+                            value={inputMode}
+                            onChange={(e) => setInputMode(e.target.value)}
+                            className="input-primary"
+                            disabled={isProcessing}
+                        >
+                            <option value="trusted">Trusted (browser input events)</option>
+                            <option value="script">Script (synthetic DOM events)</option>
+                        </select>
+                    </div>
+
                     {/* Show fill input for text elements when fill action is selected */}
                     {(selectedActionType === "fill" || selectedActionType === "set_value") && isTextInputElement && (
                         <div>
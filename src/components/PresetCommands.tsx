@@ -1,4 +1,4 @@
-import {CommandResponse, ParsedCommand} from "../types/Control.tsx";
+import {CommandResponse, ParsedCommand, errorMessage} from "../types/Control.tsx";
 import {invoke} from "@tauri-apps/api/core";
 
 interface ParsedCommandsProps {
@@ -34,7 +34,7 @@ export default function PresetCommands({
         } catch (error) {
             setResult({
                 success: false,
-                message: `Error: ${error}`
+                message: `Error: ${errorMessage(error)}`
             });
         } finally {
             setIsProcessing(false);
@@ -1,5 +1,5 @@
 import {invoke} from "@tauri-apps/api/core";
-import {ChromeSession, CommandResponse, PageElement, PageHints} from "../types/Control.tsx";
+import {ChromeSession, CommandResponse, PageElement, PageHints, errorMessage} from "../types/Control.tsx";
 import {useState} from "react";
 import HintSelection from "./HintSelection.tsx";
 
@@ -39,12 +39,15 @@ export default function VimiumShow({chromeSession, isProcessing, setResult, setI
             setHintFilter("");
             setResult({
                 success: true,
-                message: `Found ${hints.visible_count} interactive elements on the page`
+                message: `Found ${hints.visible_count} interactive elements on the page` +
+                    (hints.skipped_frames.length > 0
+                        ? ` (${hints.skipped_frames.length} frames could not be searched)`
+                        : "")
             });
         } catch (error) {
             setResult({
                 success: false,
-                message: `Failed to show page hints: ${error}`
+                message: `Failed to show page hints: ${errorMessage(error)}`
             });
         } finally {
             setIsProcessing(false);
@@ -71,7 +74,7 @@ export default function VimiumShow({chromeSession, isProcessing, setResult, setI
         } catch (error) {
             setResult({
                 success: false,
-                message: `Failed to clear hints: ${error}`
+                message: `Failed to clear hints: ${errorMessage(error)}`
             });
         } finally {
             setIsProcessing(false);
@@ -1,5 +1,5 @@
 import {useCallback, useEffect, useRef, useState} from "react";
-import {ChromeSession, CommandResponse} from "../types/Control.tsx";
+import {ChromeSession, CommandResponse, errorMessage} from "../types/Control.tsx";
 
 interface VoiceControlProps {
     isProcessing: boolean;
@@ -335,9 +335,9 @@ export default function VoiceControl({
         } catch (error) {
             setResult({
                 success: false,
-                message: `Voice command execution failed: ${error}`
+                message: `Voice command execution failed: ${errorMessage(error)}`
             });
-            log(`Voice command failed: ${error}`, 'error');
+            log(`Voice command failed: ${errorMessage(error)}`, 'error');
         } finally {
             setIsProcessing(false);
         }
@@ -9,9 +9,42 @@ export interface ParsedCommand {
 }
 
 export interface ChromeControlOptions {
+    label?: string; // Name shown for the session
     url?: string;
     profile?: string;
+    user_data_dir?: string; // Defaults to the browser's own user-data dir
     debug_port?: number;
+    auto_relaunch?: boolean; // Restart a launched Chrome that crashed, restoring its tabs
+    ephemeral?: boolean; // Start in a fresh temporary user-data dir
+    keep_profile?: boolean; // Keep the temporary user-data dir when the session closes
+    template_dir?: string; // User-data dir copied into the temporary one
+    preset?: string; // Saved preset these options are applied on top of
+    headless?: boolean;
+    window_size?: WindowSize;
+    proxy_server?: string; // e.g. "socks5://127.0.0.1:1080"
+    extensions?: string[]; // Directories of unpacked extensions
+    extra_args?: string[];
+    browser_path?: string; // Defaults to the first Chrome found on the system
+    startup_timeout_secs?: number; // How long a launched browser gets to become ready
+}
+
+export interface WindowSize {
+    width: number;
+    height: number;
+}
+
+export interface LaunchPreset {
+    name: string;
+    options: ChromeControlOptions;
+}
+
+export interface ChromeProfile {
+    browser: string;
+    user_data_dir: string;
+    directory: string; // "Default", "Profile 1", ...
+    name: string;
+    avatar?: string; // Path of the profile picture, else Chrome's avatar icon id
+    last_used?: number; // Seconds since the epoch
 }
 
 export interface ChromeTarget {
@@ -25,6 +58,27 @@ export interface ChromeTarget {
 export interface ChromeSession {
     debug_port: number;
     session_id: string;
+    label?: string; // Name the user gave the session
+    profile?: string;
+    user_data_dir?: string;
+    browser?: string; // e.g. "Chrome/126.0.6478.126"
+    protocol_version?: string;
+    executable?: string; // Binary the session launched, if it launched one
+    launch?: Record<string, unknown>; // Launch options and temporary profile, when the app started the browser
+}
+
+export interface SessionSummary {
+    session_id: string;
+    label?: string;
+    debug_port: number;
+    browser?: string;
+    owned: boolean; // Whether the app launched this Chrome and controls its lifetime
+}
+
+export interface InstalledBrowser {
+    name: string;
+    path: string;
+    version?: string;
 }
 
 export interface PageElement {
@@ -39,12 +93,20 @@ export interface PageElement {
     height: number;
     visible: boolean;
     selector: string;
+    frame_id?: string; // Iframe the element is in; unset for the main frame
+}
+
+export interface SkippedFrame {
+    frame_id: string;
+    url: string;
+    reason: string;
 }
 
 export interface PageHints {
     elements: PageElement[];
     total_count: number;
     visible_count: number;
+    skipped_frames: SkippedFrame[]; // Iframes whose elements could not be hinted
 }
 
 export interface ElementAction {
@@ -52,4 +114,61 @@ export interface ElementAction {
     action_type: string; // "click", "hover", "focus", "right_click", "fill", "set_value"
     modifier_keys?: string[];
     value?: string; // For fill/set_value operations
-}
\ No newline at end of file
+    input_mode?: string; // "trusted" (default) or "script"
+    frame_id?: string; // Frame of the hinted element, from its PageElement
+}
+
+export interface ChromeEventPayload {
+    session_id: string;
+    target_id: string;
+    method: string;
+    params: unknown;
+}
+
+export interface ConsoleEntry {
+    target_id: string;
+    source: string; // "console", "exception" or a Log domain source
+    level: string; // "debug", "log", "info", "warning", "error"
+    text: string;
+    url?: string;
+    line_number?: number;
+    timestamp: number; // Milliseconds since the epoch
+}
+
+export interface ConsoleEventPayload {
+    session_id: string;
+    entry: ConsoleEntry;
+}
+
+export interface NavigationResult {
+    url: string; // Where the tab ended up, after redirects
+    status?: number; // HTTP status of the document
+    error?: string; // e.g. "net::ERR_NAME_NOT_RESOLVED"
+}
+
+// Overrides of the session's CDP call defaults for a single command
+export interface CallOptions {
+    timeout_ms?: number;
+    retries?: number;
+}
+
+export interface AppSettings {
+    cdp_timeout_ms: number; // How long a CDP request waits for its response
+    cdp_retries: number; // Extra attempts of read-only calls after a timeout or a page navigating mid-call
+    cdp_retry_delay_ms: number;
+    close_browsers_on_exit: boolean; // Kill the browsers the app launched when it quits
+}
+
+// Error returned by every backend command; `code` is stable, `message` is for display
+export interface CommandError {
+    code: string; // "session_not_found", "devtools_unreachable", "no_target", "cdp_error", "script_exception", "timeout", "cancelled", "stt_failed", "parse_failed", "failed"
+    message: string;
+    details: Record<string, unknown>; // Has a "context" string when the error happened during a larger operation
+}
+
+export const errorMessage = (error: unknown): string => {
+    if (error && typeof error === "object" && "message" in error) {
+        return String((error as CommandError).message);
+    }
+    return String(error);
+};